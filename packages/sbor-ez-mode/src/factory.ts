@@ -14,6 +14,8 @@ import { MapDefinition, MapSchema } from './schemas/map';
 import { InternalAddressSchema } from './schemas/internalAddress';
 import { InstantSchema } from './schemas/instant';
 import { OptionSchema } from './schemas/option';
+import { BytesSchema } from './schemas/bytes';
+import { BigIntSchema, IntegerStringSchema } from './schemas/bigint';
 
 /**
  * The main object through which to build schemas for parsing SBOR values.
@@ -60,11 +62,28 @@ export const s = {
      * @returns NonFungibleLocalIdSchema
      */
     nonFungibleLocalId: () => new NonFungibleLocalIdSchema(),
+    /**
+     * A schema for Bytes, such as a Vec<u8> or [u8; N]. This will parse the bytes into a hex string
+     * @returns BytesSchema
+     */
+    bytes: () => new BytesSchema(),
     /**
      * A schema for a Number
      * @returns NumberSchema
      */
     number: () => new NumberSchema(),
+    /**
+     * A schema for any integer, parsed into a bigint. Unlike s.number(), this doesn't lose
+     * precision for 64 and 128 bit integers
+     * @returns BigIntSchema
+     */
+    bigint: () => new BigIntSchema(),
+    /**
+     * A schema for any integer, parsed into its decimal string representation. Unlike s.number(),
+     * this doesn't lose precision for 64 and 128 bit integers
+     * @returns IntegerStringSchema
+     */
+    integerString: () => new IntegerStringSchema(),
     /**
      * A schema for an Instant. This will parse the instant directly into a Date object
      * @returns InstantSchema
@@ -152,6 +171,9 @@ export const s = {
      * @param variants An array of variant definitions, which are objects with a string variant name and a schema.
      * The schema passed as "value" must be either a StructSchema or an OrderedTupleSchema. This has to do with
      * the fact that Rust represents enum values either with struct syntax or tuple syntax.
+     * @param fallback An optional schema for variants that are not in the definition, such as `s.value()`.
+     * Those are parsed into the name (or id, if unnamed) of the variant together with the fallback value,
+     * which keeps parsing working when a blueprint gains new variants.
      * @returns EnumSchema
      * @example
      * ```ts
@@ -182,6 +204,7 @@ export const s = {
      *   variant: 'Fungible',
      *   value: ["12345"]
      * }
+     * ```
      */
     enum: <
         const VARIANT_DEF extends VariantDefinition<S>[],
@@ -189,9 +212,11 @@ export const s = {
         T extends TupleSchema,
         D extends StructDefinition,
         B extends boolean,
+        F = never,
     >(
-        variants: VARIANT_DEF
-    ): EnumSchema<VARIANT_DEF> => new EnumSchema(variants),
+        variants: VARIANT_DEF,
+        fallback?: SborSchema<F>
+    ): EnumSchema<VARIANT_DEF, F> => new EnumSchema(variants, fallback),
     /**
      * A utility schema for the Option enum in Rust. This is a common pattern in Rust to represent
      * nullable values. The Option schema takes a single schema as an argument, which describes the
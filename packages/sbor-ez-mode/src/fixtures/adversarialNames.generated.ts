@@ -0,0 +1,95 @@
+import s from '@calamari-radix/sbor-ez-mode';
+// Generated TypeScript schema for Scrypto SBOR types of package address: package_adversarial
+//Generated by: https://www.8arms1goal.com/sbor-ez-mode-ez-mode
+
+export const Vec_u8 = s.enum([
+    { variant: "Say \"hi\"", id: 0 },
+    { variant: "*/ break", id: 1, schema: s.tuple([s.string()]) },
+]);
+
+export const class_ = s.struct({
+    "my-field": s.string(),
+    constructor: s.string(),
+});
+
+export const HashMap_K_V = s.struct({ "0th": Vec_u8, value: class_ });
+
+export const Pool = { state: HashMap_K_V, events: { "my-event": class_ } };
+
+export const eventSchemas = { Pool: Pool.events };
+
+type EventSchemas = typeof eventSchemas;
+type ProgrammaticJson = s.infer<ReturnType<typeof s.value>>;
+type Parsed<S> = S extends { parse(...args: never[]): infer T } ? T : never;
+
+export type KnownEvent = {
+    [B in keyof EventSchemas]: {
+        [E in keyof EventSchemas[B]]: {
+            known: true;
+            blueprint: B;
+            event: E;
+            data: Parsed<EventSchemas[B][E]>;
+        };
+    }[keyof EventSchemas[B]];
+}[keyof EventSchemas];
+
+export type UnknownEvent = {
+    known: false;
+    blueprint: string;
+    event: string;
+    data: ProgrammaticJson;
+};
+
+type EventSchema = {
+    parse(value: ProgrammaticJson, path: string[]): unknown;
+};
+const eventTable: Record<string, Record<string, EventSchema> | undefined> =
+    eventSchemas;
+
+/**
+ * Decode the programmatic JSON payload of an event, based on the blueprint
+ * that emitted it and the name of the event. Events that are not part of this
+ * package are returned as-is.
+ * @throws {SborError} If the payload doesn't match the schema of the event
+ */
+export function decodeEvent(
+    emitterBlueprint: string,
+    eventName: string,
+    programmaticJson: ProgrammaticJson
+): KnownEvent | UnknownEvent {
+    const schema = eventTable[emitterBlueprint]?.[eventName];
+    if (!schema) {
+        return {
+            known: false,
+            blueprint: emitterBlueprint,
+            event: eventName,
+            data: programmaticJson,
+        };
+    }
+    return {
+        known: true,
+        blueprint: emitterBlueprint,
+        event: eventName,
+        data: schema.parse(programmaticJson, []),
+    } as KnownEvent;
+}
+
+export type Vec_u8 =
+    | { variant: "Say \"hi\"" }
+    | { variant: "*/ break"; value: [string] };
+
+export interface class_ {
+    "my-field": string;
+    constructor: string;
+}
+
+export interface HashMap_K_V {
+    "0th": Vec_u8;
+    value: class_;
+}
+
+export interface Pool {
+    state: HashMap_K_V;
+    events: { "my-event": class_ };
+}
+
@@ -3,6 +3,7 @@ import { s } from './factory';
 import { ProgrammaticScryptoSborValue } from '@radixdlt/babylon-gateway-api-sdk';
 import { SborError, SborSchema } from './sborSchema';
 import { Result } from 'neverthrow';
+import * as adversarialNames from './fixtures/adversarialNames.generated';
 
 function evaluateResultHelper<T, E>(
     schema: SborSchema<T>,
@@ -843,6 +844,42 @@ describe('sbor', () => {
         expectTypeOf(result).toEqualTypeOf<Date>();
     });
 
+    it('parse a wide integer without losing precision', () => {
+        const example: ProgrammaticScryptoSborValue = {
+            kind: 'U128',
+            field_name: 'total_supply',
+            value: '340282366920938463463374607431768211455',
+        };
+
+        const bigintResult = evaluateResultHelper(
+            s.bigint(),
+            example,
+            340282366920938463463374607431768211455n
+        );
+        expectTypeOf(bigintResult).toEqualTypeOf<bigint>();
+
+        const stringResult = evaluateResultHelper(
+            s.integerString(),
+            example,
+            '340282366920938463463374607431768211455'
+        );
+        expectTypeOf(stringResult).toEqualTypeOf<string>();
+    });
+
+    it('parse bytes', () => {
+        const example: ProgrammaticScryptoSborValue = {
+            kind: 'Bytes',
+            element_kind: 'U8',
+            field_name: 'hash',
+            hex: 'deadbeef',
+        };
+
+        const parsed = 'deadbeef';
+        const schema = s.bytes();
+        const result = evaluateResultHelper(schema, example, parsed);
+        expectTypeOf(result).toEqualTypeOf<string>();
+    });
+
     it('parse a None', () => {
         const example: ProgrammaticScryptoSborValue = {
             kind: 'Enum',
@@ -915,6 +952,219 @@ describe('sbor', () => {
         expectTypeOf(result).toEqualTypeOf<expectedType>();
     });
 
+    it('parse an Option of a tuple', () => {
+        // Generated for an `Option<(u32, String)>`, the `Some` variant has a single tuple field
+        const example: ProgrammaticScryptoSborValue = {
+            kind: 'Enum',
+            variant_id: '1',
+            variant_name: 'Some',
+            fields: [
+                {
+                    kind: 'Tuple',
+                    fields: [
+                        { kind: 'U32', value: '5' },
+                        { kind: 'String', value: 'hello' },
+                    ],
+                },
+            ],
+        };
+
+        const parsed = {
+            variant: 'Some',
+            value: [5, 'hello'],
+        };
+
+        const schema = s.option(s.tuple([s.number(), s.string()]));
+        type expectedType =
+            | {
+                  variant: 'Some';
+                  value: [number, string];
+              }
+            | {
+                  variant: 'None';
+              };
+        const result = evaluateResultHelper(schema, example, parsed);
+        expectTypeOf(result).toEqualTypeOf<expectedType>();
+    });
+
+    it('parse an enum with unit variants', () => {
+        const examples: ProgrammaticScryptoSborValue[] = [
+            {
+                kind: 'Enum',
+                variant_id: '0',
+                variant_name: 'Open',
+                type_name: 'PoolStatus',
+                fields: [],
+            },
+            {
+                kind: 'Enum',
+                variant_id: '1',
+                variant_name: 'Paused',
+                type_name: 'PoolStatus',
+                fields: [{ kind: 'String', value: 'maintenance' }],
+            },
+        ];
+
+        const parsed = [
+            { variant: 'Open' },
+            { variant: 'Paused', value: ['maintenance'] },
+        ];
+
+        // Generated for `enum PoolStatus { Open, Paused(String) }`
+        const schema = s.enum([
+            { variant: 'Open' },
+            { variant: 'Paused', schema: s.tuple([s.string()]) },
+        ]);
+        type expectedType =
+            | {
+                  variant: 'Open';
+              }
+            | {
+                  variant: 'Paused';
+                  value: [string];
+              };
+
+        examples.forEach((example, i) => {
+            const result = evaluateResultHelper(schema, example, parsed[i]);
+            expectTypeOf(result).toEqualTypeOf<expectedType>();
+        });
+
+        const withFields: ProgrammaticScryptoSborValue = {
+            kind: 'Enum',
+            variant_id: '0',
+            variant_name: 'Open',
+            fields: [{ kind: 'String', value: 'unexpected' }],
+        };
+        expect(schema.safeParse(withFields).isErr()).toBe(true);
+    });
+
+    it('parse enum variants by their id', () => {
+        // Variants without a name in the schema only have an id in the programmatic JSON
+        const examples: ProgrammaticScryptoSborValue[] = [
+            {
+                kind: 'Enum',
+                variant_id: '3',
+                fields: [{ kind: 'U8', value: '7' }],
+            },
+            {
+                kind: 'Enum',
+                variant_id: '7',
+                variant_name: 'Named',
+                fields: [],
+            },
+        ];
+
+        const parsed = [{ variant: 'Variant3', value: [7] }, { variant: 'Named' }];
+
+        const schema = s.enum([
+            { variant: 'Variant3', id: 3, schema: s.tuple([s.number()]) },
+            { variant: 'Named', id: 7 },
+        ]);
+        type expectedType =
+            | {
+                  variant: 'Variant3';
+                  value: [number];
+              }
+            | {
+                  variant: 'Named';
+              };
+
+        examples.forEach((example, i) => {
+            const result = evaluateResultHelper(schema, example, parsed[i]);
+            expectTypeOf(result).toEqualTypeOf<expectedType>();
+        });
+
+        const unknown: ProgrammaticScryptoSborValue = {
+            kind: 'Enum',
+            variant_id: '4',
+            fields: [],
+        };
+        expect(schema.safeParse(unknown).isErr()).toBe(true);
+    });
+
+    it('parse an unknown enum variant with a fallback', () => {
+        // A variant added in a newer version of the blueprint
+        const example: ProgrammaticScryptoSborValue = {
+            kind: 'Enum',
+            variant_id: '2',
+            variant_name: 'Closed',
+            fields: [{ kind: 'String', value: 'sunset' }],
+        };
+
+        const parsed = { variant: 'Closed', value: example };
+
+        const schema = s.enum(
+            [
+                { variant: 'Open', id: 0 },
+                { variant: 'Paused', id: 1, schema: s.tuple([s.string()]) },
+            ],
+            s.value()
+        );
+        type expectedType =
+            | {
+                  variant: 'Open';
+              }
+            | {
+                  variant: 'Paused';
+                  value: [string];
+              }
+            | {
+                  variant: string;
+                  value: ProgrammaticScryptoSborValue;
+              };
+
+        const result = evaluateResultHelper(schema, example, parsed);
+        expectTypeOf(result).toEqualTypeOf<expectedType>();
+    });
+
+    it('parse with a generated schema of adversarial names', () => {
+        // Rendered by schema-gen from type, field and variant names that need escaping
+        const classValue: ProgrammaticScryptoSborValue = {
+            kind: 'Tuple',
+            type_name: 'class',
+            fields: [
+                { kind: 'String', field_name: 'my-field', value: 'a' },
+                { kind: 'String', field_name: 'constructor', value: 'b' },
+            ],
+        };
+        const example: ProgrammaticScryptoSborValue = {
+            kind: 'Tuple',
+            type_name: 'HashMap<K, V>',
+            fields: [
+                {
+                    kind: 'Enum',
+                    field_name: '0th',
+                    variant_id: '1',
+                    variant_name: '*/ break',
+                    fields: [{ kind: 'String', value: 'x' }],
+                },
+                { ...classValue, field_name: 'value' },
+            ],
+        };
+
+        const parsed = {
+            '0th': { variant: '*/ break', value: ['x'] },
+            value: { 'my-field': 'a', constructor: 'b' },
+        };
+        const state = evaluateResultHelper(
+            adversarialNames.Pool.state,
+            example,
+            parsed
+        );
+        // The generated types describe exactly what the schemas parse into
+        expectTypeOf(state).toEqualTypeOf<adversarialNames.HashMap_K_V>();
+        expectTypeOf(state).toEqualTypeOf<adversarialNames.Pool['state']>();
+
+        expect(
+            adversarialNames.decodeEvent('Pool', 'my-event', classValue)
+        ).toEqual({
+            known: true,
+            blueprint: 'Pool',
+            event: 'my-event',
+            data: { 'my-field': 'a', constructor: 'b' },
+        });
+    });
+
     it('parse an array of non fungible local ids', () => {
         const example: ProgrammaticScryptoSborValue = {
             kind: 'Array',
@@ -0,0 +1,46 @@
+import { ProgrammaticScryptoSborValue } from '@radixdlt/babylon-gateway-api-sdk';
+import { SborSchema } from '../sborSchema';
+import {
+    integerKinds,
+    ProgrammaticScryptoSborValueNumber,
+    validateInteger,
+} from './number';
+
+/**
+ * Parses any integer into a bigint, so that 64 and 128 bit integers don't lose precision.
+ */
+export class BigIntSchema extends SborSchema<bigint> {
+    constructor() {
+        super(integerKinds);
+    }
+
+    validate(value: ProgrammaticScryptoSborValue, path: string[]): boolean {
+        return validateInteger(value, path, this.kinds);
+    }
+
+    parse(value: ProgrammaticScryptoSborValue, path: string[]): bigint {
+        this.validate(value, path);
+        const number = value as ProgrammaticScryptoSborValueNumber;
+        return BigInt(number.value);
+    }
+}
+
+/**
+ * Parses any integer into its decimal string representation, so that 64 and 128 bit integers
+ * don't lose precision.
+ */
+export class IntegerStringSchema extends SborSchema<string> {
+    constructor() {
+        super(integerKinds);
+    }
+
+    validate(value: ProgrammaticScryptoSborValue, path: string[]): boolean {
+        return validateInteger(value, path, this.kinds);
+    }
+
+    parse(value: ProgrammaticScryptoSborValue, path: string[]): string {
+        this.validate(value, path);
+        const number = value as ProgrammaticScryptoSborValueNumber;
+        return number.value;
+    }
+}
@@ -11,7 +11,16 @@ export interface VariantDefinition<
     S extends StructSchema<any, any> | OrderedTupleSchema<any>,
 > {
     variant: string;
-    schema: S;
+    /**
+     * The discriminator of the variant. When given, values are also matched on their variant id,
+     * which is needed for enums whose variants have no names in the programmatic JSON.
+     */
+    id?: number;
+    /**
+     * The schema of the fields of the variant. Leave this out for unit variants without fields,
+     * which are parsed into just `{ variant }`.
+     */
+    schema?: S;
 }
 
 // Helper types to extract parsed and output types from variant schemas
@@ -22,32 +31,57 @@ type VariantParsedType<T extends VariantDefinition<any>> =
           ? { [K in keyof U]: ParsedType<U[K]> }
           : never;
 
+type VariantOutputType<T extends VariantDefinition<any>> = T extends {
+    schema: StructSchema<any, any> | OrderedTupleSchema<any>;
+}
+    ? { variant: T['variant']; value: VariantParsedType<T> }
+    : { variant: T['variant'] };
+
 export type EnumParsedType<T extends VariantDefinition<any>[]> = {
-    [K in keyof T]: T[K] extends VariantDefinition<infer S>
-        ? S extends StructSchema<any, any>
-            ? {
-                  variant: T[K]['variant'];
-                  value: VariantParsedType<T[K]>;
-              }
-            : S extends OrderedTupleSchema<any>
-              ? { variant: T[K]['variant']; value: VariantParsedType<T[K]> }
-              : never
+    [K in keyof T]: T[K] extends VariantDefinition<any>
+        ? VariantOutputType<T[K]>
         : never;
 }[number];
 
-export class EnumSchema<T extends VariantDefinition<any>[]> extends SborSchema<
-    {
-        [K in keyof T]: T[K] extends VariantDefinition<any>
-            ? { variant: T[K]['variant']; value: VariantParsedType<T[K]> }
-            : never;
-    }[number]
-> {
+// The parsed type of a variant that is not in the definition, when a fallback schema is given
+export type FallbackParsedType<F> = [F] extends [never]
+    ? never
+    : { variant: string; value: F };
+
+export class EnumSchema<
+    T extends VariantDefinition<any>[],
+    F = never,
+> extends SborSchema<EnumParsedType<T> | FallbackParsedType<F>> {
     private variants: Map<string, VariantDefinition<any>>;
+    private variantsById: Map<string, VariantDefinition<any>>;
+    private fallback?: SborSchema<F>;
 
-    constructor(variants: T) {
+    /**
+     * @param variants The definitions of the variants.
+     * @param fallback If given, variants that are not in the definition are parsed with this schema
+     *                 instead of being a parsing error.
+     */
+    constructor(variants: T, fallback?: SborSchema<F>) {
         super(['Enum']);
+        this.fallback = fallback;
         this.variants = new Map(variants.map((v) => [v.variant, v]));
+        this.variantsById = new Map(
+            variants
+                .filter((v) => v.id !== undefined)
+                .map((v) => [String(v.id), v])
+        );
+    }
+
+    // Find a variant by its name, falling back to its id
+    private findVariant(
+        enumValue: ProgrammaticScryptoSborValueEnum
+    ): VariantDefinition<any> | undefined {
+        const byName = enumValue.variant_name
+            ? this.variants.get(enumValue.variant_name)
+            : undefined;
+        return byName ?? this.variantsById.get(enumValue.variant_id);
     }
+
     validate(value: ProgrammaticScryptoSborValue, path: string[]): boolean {
         if (
             !value ||
@@ -61,15 +95,17 @@ export class EnumSchema<T extends VariantDefinition<any>[]> extends SborSchema<
         const enumValue = value as ProgrammaticScryptoSborValueEnum;
 
         // Validate variant exists
-        const variantName = enumValue.variant_name;
-        if (!variantName || !this.variants.has(variantName)) {
+        const variantDef = this.findVariant(enumValue);
+        if (!variantDef && this.fallback) {
+            return this.fallback.validate(value, path);
+        }
+        if (!variantDef) {
             throw new SborError(
-                `Unknown variant: ${variantName || 'undefined'}`,
+                `Unknown variant: ${enumValue.variant_name || `#${enumValue.variant_id}`}`,
                 path
             );
         }
-
-        const variantDef = this.variants.get(variantName)!;
+        const variantName = variantDef.variant;
 
         // If the variant has no schema (empty variant), fields should be empty
         if (!variantDef.schema) {
@@ -95,11 +131,19 @@ export class EnumSchema<T extends VariantDefinition<any>[]> extends SborSchema<
     parse(
         value: ProgrammaticScryptoSborValue,
         path: string[]
-    ): EnumParsedType<T> {
+    ): EnumParsedType<T> | FallbackParsedType<F> {
         this.validate(value, path);
         const enumValue = value as ProgrammaticScryptoSborValueEnum;
-        const variantName = enumValue.variant_name!;
-        const variantDef = this.variants.get(variantName)!;
+        const variantDef = this.findVariant(enumValue);
+
+        // Unknown variant, which passed validation through the fallback
+        if (!variantDef) {
+            return {
+                variant: enumValue.variant_name || enumValue.variant_id,
+                value: this.fallback!.parse(value, path),
+            } as FallbackParsedType<F>;
+        }
+        const variantName = variantDef.variant;
 
         // Empty variant
         if (!variantDef.schema) {
@@ -0,0 +1,14 @@
+import path from 'path';
+import { defineConfig } from 'vitest/config';
+
+export default defineConfig({
+    resolve: {
+        alias: {
+            // Generated schemas in src/fixtures import the package by name
+            '@calamari-radix/sbor-ez-mode': path.resolve(
+                __dirname,
+                'src/index.ts'
+            ),
+        },
+    },
+});
@@ -1,4 +1,8 @@
-use schema_gen::ez_mode_gen::generate_ir;
+use schema_gen::ez_mode_gen::{generate_ir, LenientConfig, WideIntegerMode};
+use schema_gen::naming::RenameMap;
+use schema_gen::pretty::PrettyOptions;
+use schema_gen::renderer::{OutputFormat, RenderOptions, Renderer, RootFilter};
+use schema_gen::sql::SqlRenderer;
 use serde_json::json;
 use vercel_runtime::{run, Body, Error, Request, Response, StatusCode};
 
@@ -10,7 +14,17 @@ async fn main() -> Result<(), Error> {
 #[derive(serde::Deserialize)]
 struct SchemaGenRequest {
     package_address: String,
+    format: Option<OutputFormat>,
     module: Option<bool>,
+    renames: Option<RenameMap>,
+    wide_integers: Option<WideIntegerMode>,
+    lenient: Option<LenientConfig>,
+    indent_width: Option<usize>,
+    line_width: Option<usize>,
+    filter: Option<RootFilter>,
+    with_types: Option<bool>,
+    /// Also return Postgres tables for indexing the package, under `sql`.
+    sql: Option<bool>,
 }
 
 fn error_response(message: &str) -> Response<Body> {
@@ -42,19 +56,65 @@ pub async fn handler(req: Request) -> Result<Response<Body>, Error> {
         }
     };
 
+    // The widths size the indentation strings, so they are bounded to keep
+    // a request from exhausting the memory of the function. The command line
+    // accepts the same ranges.
+    let pretty = PrettyOptions {
+        indent_width: input.indent_width.unwrap_or(4),
+        line_width: input.line_width.unwrap_or(80),
+    };
+    if !(1..=16).contains(&pretty.indent_width) || pretty.line_width > 400 {
+        return Ok(error_response("Invalid input."));
+    }
+    // As on the command line, leaving out both the state and the events
+    // would leave nothing to render.
+    let filter = input.filter.unwrap_or_default();
+    if filter.events_only && filter.state_only {
+        return Ok(error_response("Invalid input."));
+    }
+
     let schemas = schema_gen::gateway::get_blueprints_and_corresponding_schemas(
         &input.package_address,
     );
     match schemas {
         Ok(schemas) => {
-            let registry = generate_ir(&schemas);
-            let schema = registry
-                .render(&input.package_address, input.module.unwrap_or(false));
+            let mut registry = generate_ir(&schemas);
+            if let Some(renames) = &input.renames {
+                registry.assign_names(renames);
+            }
+            let options = RenderOptions {
+                module: input.module.unwrap_or(false),
+                wide_integers: input.wide_integers.unwrap_or_default(),
+                lenient: input.lenient.unwrap_or_default(),
+                pretty,
+                filter,
+                with_types: input.with_types.unwrap_or(false),
+            };
+            let format = input.format.unwrap_or_default();
+            let schema = format.renderer().render(
+                &registry,
+                &input.package_address,
+                &options,
+            );
+            let mut warnings = match &input.renames {
+                Some(renames) => registry.rename_warnings(renames),
+                None => Vec::new(),
+            };
+            warnings.extend(format.warnings(&registry, &options));
+            let mut body = json!({
+                "schema": schema,
+                "warnings": warnings,
+            });
+            if input.sql.unwrap_or(false) {
+                body["sql"] = SqlRenderer
+                    .render(&registry, &input.package_address, &options)
+                    .into();
+            }
             Ok(Response::builder()
                 .status(StatusCode::OK)
                 .header("Access-Control-Allow-Origin", "*")
                 .header("Content-Type", "application/json")
-                .body(json!({ "schema": schema }).to_string().into())?)
+                .body(body.to_string().into())?)
         }
         Err(e) => Ok(Response::builder()
             .status(StatusCode::BAD_REQUEST)
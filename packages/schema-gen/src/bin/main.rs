@@ -1,6 +1,14 @@
 use clap::{Parser, Subcommand};
 use sbor::Schema;
-use schema_gen::ez_mode_gen::generate_ir;
+use schema_gen::ez_mode_gen::{
+    generate_ir, LenientConfig, SchemaRegistry, WideIntegerMode,
+};
+use schema_gen::gateway::BlueprintWithSchema;
+use schema_gen::graph::{GraphFormat, GraphRenderer};
+use schema_gen::naming::RenameMap;
+use schema_gen::pretty::PrettyOptions;
+use schema_gen::renderer::{OutputFormat, RenderOptions, Renderer, RootFilter};
+use schema_gen::sql::SqlRenderer;
 
 use scrypto::prelude::{scrypto_decode, ScryptoCustomSchema};
 
@@ -17,17 +25,157 @@ enum Command {
     /// List all available type names in the schema.
     List { package_address: String },
     /// Generate TS schema from the package address.
+    #[clap(visible_alias = "render")]
     Gen {
-        package_address: String,
+        #[clap(
+            required_unless_present_any = ["from_ir", "package_definition"]
+        )]
+        package_address: Option<String>,
+        #[clap(
+            long,
+            value_enum,
+            default_value_t = OutputFormat::EzMode,
+            help = "The output format"
+        )]
+        format: OutputFormat,
         #[clap(
             long,
             short,
             help = "Render as a ready-to-use module with export and import syntax"
         )]
         module: bool,
+        #[clap(
+            long,
+            help = "JSON file mapping generated type names to the names to use instead"
+        )]
+        rename_map: Option<String>,
+        #[clap(
+            long,
+            value_enum,
+            default_value_t = WideIntegerMode::Number,
+            help = "How to render integers wider than 32 bits"
+        )]
+        wide_integers: WideIntegerMode,
+        #[clap(
+            long,
+            help = "Allow missing struct fields and unknown enum variants, for newer versions of the package"
+        )]
+        lenient: bool,
+        #[clap(
+            long,
+            help = "JSON file selecting which types to render leniently, by generated name"
+        )]
+        lenient_config: Option<String>,
+        #[clap(
+            long,
+            default_value_t = 4,
+            value_parser = clap::value_parser!(u16).range(1..=16),
+            help = "Number of spaces per indentation level"
+        )]
+        indent_width: u16,
+        #[clap(
+            long,
+            default_value_t = 80,
+            value_parser = clap::value_parser!(u16).range(..=400),
+            help = "Line length to wrap the generated code at"
+        )]
+        line_width: u16,
+        #[clap(
+            long = "blueprint",
+            help = "Only render this blueprint, can be repeated"
+        )]
+        blueprints: Vec<String>,
+        #[clap(
+            long = "type",
+            help = "Render this type by its generated name, can be repeated"
+        )]
+        types: Vec<String>,
+        #[clap(
+            long,
+            conflicts_with = "state_only",
+            help = "Leave out the state of the blueprints"
+        )]
+        events_only: bool,
+        #[clap(long, help = "Leave out the events of the blueprints")]
+        state_only: bool,
+        #[clap(
+            long,
+            help = "Read the blueprints from a package definition (.rpd) file instead of the gateway"
+        )]
+        package_definition: Option<String>,
+        #[clap(
+            long,
+            help = "Also declare the types of the parsed values, next to the sbor-ez-mode schemas"
+        )]
+        with_types: bool,
+        #[clap(
+            long,
+            help = "Also write Postgres tables for indexing the events and state to this file"
+        )]
+        sql: Option<String>,
+        #[clap(
+            long,
+            conflicts_with = "package_definition",
+            help = "Render the JSON IR from this file instead of fetching the package"
+        )]
+        from_ir: Option<String>,
+    },
+    /// Export the type graph of the package as Mermaid or Graphviz DOT.
+    Graph {
+        #[clap(required_unless_present = "package_definition")]
+        package_address: Option<String>,
+        #[clap(
+            long,
+            value_enum,
+            default_value_t = GraphFormat::Mermaid,
+            help = "The graph syntax"
+        )]
+        format: GraphFormat,
+        #[clap(
+            long = "blueprint",
+            help = "Only export this blueprint, can be repeated"
+        )]
+        blueprints: Vec<String>,
+        #[clap(
+            long,
+            help = "Read the blueprints from a package definition (.rpd) file instead of the gateway"
+        )]
+        package_definition: Option<String>,
     },
 }
 
+/// Read a rename map or lenient config from a JSON file.
+fn read_json_file<T: serde::de::DeserializeOwned>(
+    path: &str,
+) -> Result<T, Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    Ok(serde_json::from_str(&contents)?)
+}
+
+/// Read the blueprints of a package from a package definition file, or
+/// from the gateway.
+fn read_blueprints(
+    package_address: &str,
+    package_definition: Option<String>,
+) -> Result<Vec<BlueprintWithSchema>, Box<dyn std::error::Error>> {
+    match package_definition {
+        Some(path) => {
+            schema_gen::package_definition::read_package_definition(&path)
+        }
+        None => schema_gen::gateway::get_blueprints_and_corresponding_schemas(
+            package_address,
+        ),
+    }
+}
+
+/// Read a registry and its package address from a JSON IR file.
+fn read_ir_file(
+    path: &str,
+) -> Result<(SchemaRegistry, String), Box<dyn std::error::Error>> {
+    let contents = std::fs::read_to_string(path)?;
+    schema_gen::ir::read_ir(&contents)
+}
+
 /// Print all available type names from the schema.
 fn print_type_names(schema: &Schema<ScryptoCustomSchema>) {
     for (i, metadata) in schema.type_metadata.iter().enumerate() {
@@ -64,18 +212,151 @@ fn main() {
         }
         Command::Gen {
             package_address,
+            format,
             module,
+            rename_map,
+            wide_integers,
+            lenient,
+            lenient_config,
+            indent_width,
+            line_width,
+            blueprints,
+            types,
+            events_only,
+            state_only,
+            package_definition,
+            with_types,
+            sql,
+            from_ir,
         } => {
-            let schemas =
-                schema_gen::gateway::get_blueprints_and_corresponding_schemas(
-                    &package_address,
-                );
-            match schemas {
-                Ok(schemas) => {
-                    let registry = generate_ir(&schemas);
+            let renames = match &rename_map {
+                Some(path) => match read_json_file(path) {
+                    Ok(renames) => renames,
+                    Err(e) => {
+                        eprintln!("Could not read rename map: {}", e);
+                        return;
+                    }
+                },
+                None => RenameMap::new(),
+            };
+            let mut lenient_config: LenientConfig = match lenient_config {
+                Some(path) => match read_json_file(&path) {
+                    Ok(config) => config,
+                    Err(e) => {
+                        eprintln!("Could not read lenient config: {}", e);
+                        return;
+                    }
+                },
+                None => LenientConfig::default(),
+            };
+            lenient_config.all |= lenient;
+            // The IR keeps the names it was exported with, unless they are
+            // renamed again.
+            let loaded = match from_ir {
+                Some(path) => read_ir_file(&path)
+                    .map(|(mut registry, package_address)| {
+                        if rename_map.is_some() {
+                            registry.assign_names(&renames);
+                        }
+                        (registry, package_address)
+                    })
+                    .map_err(|e| format!("Could not read IR: {}", e)),
+                None => {
+                    let package_address = package_address.unwrap_or_default();
+                    read_blueprints(&package_address, package_definition)
+                        .map(|schemas| {
+                            let mut registry = generate_ir(&schemas);
+                            registry.assign_names(&renames);
+                            (registry, package_address)
+                        })
+                        .map_err(|e| {
+                            format!("Could not get package information: {}", e)
+                        })
+                }
+            };
+            match loaded {
+                // The bindings are declared for a package address, so they
+                // can't be generated from a package definition alone.
+                Ok((_, package_address))
+                    if format == OutputFormat::ExternBlueprint
+                        && package_address.is_empty() =>
+                {
+                    eprintln!(
+                        "The extern-blueprint format needs the package \
+                         address, pass it next to --package-definition"
+                    );
+                }
+                Ok((registry, package_address)) => {
+                    let options = RenderOptions {
+                        module,
+                        wide_integers,
+                        lenient: lenient_config,
+                        pretty: PrettyOptions {
+                            indent_width: indent_width.into(),
+                            line_width: line_width.into(),
+                        },
+                        filter: RootFilter {
+                            blueprints: blueprints.into_iter().collect(),
+                            types: types.into_iter().collect(),
+                            events_only,
+                            state_only,
+                        },
+                        with_types,
+                    };
+                    let warnings = registry
+                        .rename_warnings(&renames)
+                        .into_iter()
+                        .chain(format.warnings(&registry, &options));
+                    for warning in warnings {
+                        eprintln!("warning: {}", warning);
+                    }
                     println!(
                         "{}\n\n",
-                        registry.render(&package_address, module)
+                        format.renderer().render(
+                            &registry,
+                            &package_address,
+                            &options
+                        )
+                    );
+                    if let Some(path) = sql {
+                        let tables = SqlRenderer.render(
+                            &registry,
+                            &package_address,
+                            &options,
+                        );
+                        if let Err(e) = std::fs::write(&path, tables) {
+                            eprintln!("Could not write {}: {}", path, e);
+                        }
+                    }
+                }
+                Err(e) => {
+                    eprintln!("{}", e);
+                }
+            }
+        }
+        Command::Graph {
+            package_address,
+            format,
+            blueprints,
+            package_definition,
+        } => {
+            let package_address = package_address.unwrap_or_default();
+            match read_blueprints(&package_address, package_definition) {
+                Ok(schemas) => {
+                    let options = RenderOptions {
+                        filter: RootFilter {
+                            blueprints: blueprints.into_iter().collect(),
+                            ..Default::default()
+                        },
+                        ..Default::default()
+                    };
+                    print!(
+                        "{}",
+                        GraphRenderer { format }.render(
+                            &generate_ir(&schemas),
+                            &package_address,
+                            &options
+                        )
                     );
                 }
                 Err(e) => {
@@ -0,0 +1,755 @@
+//! Rendering of the registry as a Markdown reference of a package, for
+//! learning the events and state of a package without reading its Scrypto
+//! source. Every blueprint gets a section with its state, collections,
+//! events and functions, followed by a section per named type. Types are
+//! written in Scrypto syntax, and named types link to their section.
+//! Types are inline HTML, so that the links work inside of code.
+
+use crate::ez_mode_gen::{
+    is_inline, BlueprintRoots, FunctionRoots, OwnKind, RegistryEntry,
+    SborEzModeSchemaKind, SchemaRegistry, Selection,
+};
+use crate::gateway::{Receiver, Visibility};
+use crate::renderer::{RenderOptions, Renderer};
+use std::collections::HashSet;
+
+/// Escapes text for inline HTML in a table cell.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('|', "\\|")
+}
+
+/// The id of the section of a named type.
+fn anchor(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+        .collect();
+    format!("type-{}", name)
+}
+
+/// Whether an entry is referred to by name, with a link to its section.
+/// Maps and tuples without a name of their own are written out instead.
+fn is_linked(entry: &RegistryEntry) -> bool {
+    match entry.schema_kind {
+        SborEzModeSchemaKind::Struct { .. }
+        | SborEzModeSchemaKind::Enum { .. } => true,
+        _ => !is_inline(entry) && entry.type_name.is_some(),
+    }
+}
+
+/// Who can call a function, for the functions table.
+fn access(visibility: &Visibility) -> String {
+    match visibility {
+        Visibility::Public => "Public".to_string(),
+        Visibility::Roles(roles) if !roles.is_empty() => format!(
+            "Roles {}",
+            roles
+                .iter()
+                .map(|role| format!("`{}`", role))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Visibility::Roles(_) => "A role of the component".to_string(),
+        Visibility::AccessRule => "An access rule".to_string(),
+        Visibility::Private => "The package only".to_string(),
+    }
+}
+
+/// Renders the registry as a Markdown reference.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DocRenderer;
+
+impl DocRenderer {
+    /// The Scrypto type of a reference to an entry, as inline HTML.
+    pub fn type_of(&self, registry: &SchemaRegistry, index: u32) -> String {
+        let entry = &registry.entries[index as usize];
+        if is_linked(entry) {
+            let name = registry.var_name(index);
+            format!("<a href=\"#{}\">{}</a>", anchor(name), escape(name))
+        } else {
+            self.inline_type(registry, entry)
+        }
+    }
+
+    fn inline_type(
+        &self,
+        registry: &SchemaRegistry,
+        entry: &RegistryEntry,
+    ) -> String {
+        let child = |index: u32| self.type_of(registry, index);
+        let text = match &entry.schema_kind {
+            SborEzModeSchemaKind::Tuple { fields } => {
+                return match fields.as_slice() {
+                    [field] => format!("({},)", child(*field)),
+                    fields => format!(
+                        "({})",
+                        fields
+                            .iter()
+                            .map(|&field| child(field))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                };
+            }
+            SborEzModeSchemaKind::Array { element_type } => {
+                return format!("Vec&lt;{}&gt;", child(*element_type));
+            }
+            SborEzModeSchemaKind::Map {
+                key_type,
+                value_type,
+            } => {
+                return format!(
+                    "IndexMap&lt;{}, {}&gt;",
+                    child(*key_type),
+                    child(*value_type)
+                );
+            }
+            SborEzModeSchemaKind::Option { some } => {
+                return format!("Option&lt;{}&gt;", child(*some));
+            }
+            SborEzModeSchemaKind::Result { ok, err } => {
+                return format!(
+                    "Result&lt;{}, {}&gt;",
+                    child(*ok),
+                    child(*err)
+                );
+            }
+            SborEzModeSchemaKind::Decimal
+            | SborEzModeSchemaKind::PreciseDecimal => entry
+                .schema_kind
+                .decimal_precision()
+                .unwrap()
+                .name
+                .to_string(),
+            SborEzModeSchemaKind::Address { reference } => reference
+                .describe()
+                .unwrap_or_else(|| "Reference".to_string()),
+            SborEzModeSchemaKind::InternalAddress { own } => {
+                own.describe().unwrap_or_else(|| "Own".to_string())
+            }
+            SborEzModeSchemaKind::Integer { bits, signed } => {
+                format!("{}{}", if *signed { "i" } else { "u" }, bits)
+            }
+            SborEzModeSchemaKind::String => "String".to_string(),
+            SborEzModeSchemaKind::Bool => "bool".to_string(),
+            SborEzModeSchemaKind::NonFungibleLocalId => {
+                "NonFungibleLocalId".to_string()
+            }
+            SborEzModeSchemaKind::Value => "ScryptoValue".to_string(),
+            SborEzModeSchemaKind::Bytes => "Vec<u8>".to_string(),
+            SborEzModeSchemaKind::WellKnown(well_known) => {
+                well_known.name().to_string()
+            }
+            SborEzModeSchemaKind::Struct { .. }
+            | SborEzModeSchemaKind::Enum { .. } => {
+                unreachable!("{:?} is referred to by name", entry.schema_kind)
+            }
+        };
+        escape(&text)
+    }
+
+    /// The type of an entry in code, followed by its bounds.
+    fn type_cell(&self, registry: &SchemaRegistry, index: u32) -> String {
+        let code = format!("<code>{}</code>", self.type_of(registry, index));
+        match &registry.entries[index as usize].validation {
+            Some(validation) => {
+                format!("{} ({})", code, escape(&validation.describe()))
+            }
+            None => code,
+        }
+    }
+
+    /// A table of the fields of a struct or tuple, or the type of any other
+    /// entry.
+    fn fields(&self, registry: &SchemaRegistry, index: u32) -> String {
+        let fields = match &registry.entries[index as usize].schema_kind {
+            kind @ (SborEzModeSchemaKind::Struct { .. }
+            | SborEzModeSchemaKind::Tuple { .. }) => kind.children(),
+            _ => {
+                return format!("{}\n", self.type_cell(registry, index));
+            }
+        };
+        self.field_table(registry, &fields, "No fields.")
+    }
+
+    fn field_table(
+        &self,
+        registry: &SchemaRegistry,
+        fields: &[(String, u32)],
+        empty: &str,
+    ) -> String {
+        if fields.is_empty() {
+            return format!("{}\n", empty);
+        }
+        let mut table = "| Field | Type |\n| --- | --- |\n".to_string();
+        for (name, field) in fields {
+            table.push_str(&format!(
+                "| `{}` | {} |\n",
+                escape(name),
+                self.type_cell(registry, *field)
+            ));
+        }
+        table
+    }
+
+    /// The fields of the state that hold many values, going by their types.
+    /// The collections that the blueprint interface declares aren't read, so
+    /// this is a heuristic.
+    fn collections(
+        &self,
+        registry: &SchemaRegistry,
+        state: u32,
+    ) -> Vec<(String, u32)> {
+        let state = &registry.entries[state as usize];
+        if !matches!(state.schema_kind, SborEzModeSchemaKind::Struct { .. }) {
+            return Vec::new();
+        }
+        state
+            .schema_kind
+            .children()
+            .into_iter()
+            .filter(|(_, field)| {
+                matches!(
+                    registry.entries[*field as usize].schema_kind,
+                    SborEzModeSchemaKind::Array { .. }
+                        | SborEzModeSchemaKind::Map { .. }
+                        | SborEzModeSchemaKind::InternalAddress {
+                            own: OwnKind::KeyValueStore
+                        }
+                )
+            })
+            .collect()
+    }
+
+    fn signature(
+        &self,
+        registry: &SchemaRegistry,
+        function: &FunctionRoots,
+    ) -> String {
+        let receiver = function.receiver.map(|receiver| match receiver {
+            Receiver::SelfRef => "&amp;self".to_string(),
+            Receiver::SelfRefMut => "&amp;mut self".to_string(),
+        });
+        let input = &registry.entries[function.input as usize];
+        let arguments: Vec<String> = match &input.schema_kind {
+            SborEzModeSchemaKind::Struct { fields } => fields
+                .iter()
+                .map(|(name, field)| {
+                    format!(
+                        "{}: {}",
+                        escape(name),
+                        self.type_of(registry, *field)
+                    )
+                })
+                .collect(),
+            SborEzModeSchemaKind::Tuple { fields } => fields
+                .iter()
+                .map(|&field| self.type_of(registry, field))
+                .collect(),
+            _ => vec![self.type_of(registry, function.input)],
+        };
+        let output = self.type_of(registry, function.output);
+        format!(
+            "<code>fn {}({}){}</code>",
+            escape(&function.name),
+            receiver
+                .into_iter()
+                .chain(arguments)
+                .collect::<Vec<_>>()
+                .join(", "),
+            if output == "()" {
+                String::new()
+            } else {
+                format!(" -&gt; {}", output)
+            }
+        )
+    }
+
+    fn blueprint(
+        &self,
+        registry: &SchemaRegistry,
+        roots: &BlueprintRoots,
+    ) -> String {
+        let mut output = format!("## `{}` blueprint\n\n", roots.blueprint);
+        if let Some(state) = roots.state {
+            output.push_str(&format!(
+                "### State\n\n{}\n",
+                self.fields(registry, state)
+            ));
+            let collections = self.collections(registry, state);
+            if !collections.is_empty() {
+                output.push_str(&format!(
+                    "### Collections\n\nThe fields of the state that hold \
+                     many values, going by their types. This is a heuristic: \
+                     collections that the blueprint declares outside of its \
+                     state aren't listed.\n\n{}\n",
+                    self.field_table(registry, &collections, "")
+                ));
+            }
+        }
+        if !roots.events.is_empty() {
+            output.push_str("### Events\n\n");
+            for (name, index) in &roots.events {
+                output.push_str(&format!(
+                    "#### `{}`\n\n{}\n",
+                    name,
+                    self.fields(registry, *index)
+                ));
+            }
+        }
+        if !roots.functions.is_empty() {
+            output.push_str(
+                "### Functions\n\n| Signature | Callable by |\n| --- | --- |\n",
+            );
+            for function in &roots.functions {
+                output.push_str(&format!(
+                    "| {} | {} |\n",
+                    self.signature(registry, function),
+                    access(&function.visibility)
+                ));
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    /// The section of a named type.
+    fn type_section(&self, registry: &SchemaRegistry, index: u32) -> String {
+        let entry = &registry.entries[index as usize];
+        let name = registry.var_name(index);
+        let (what, body) = match &entry.schema_kind {
+            SborEzModeSchemaKind::Struct { .. } => {
+                ("A struct", self.fields(registry, index))
+            }
+            SborEzModeSchemaKind::Enum { variants } => {
+                let mut table =
+                    "| Variant | Id | Fields |\n| --- | --- | --- |\n"
+                        .to_string();
+                for variant in variants {
+                    let fields = variant.payload.map(|payload| match &registry
+                        .entries[payload as usize]
+                        .schema_kind
+                    {
+                        SborEzModeSchemaKind::Struct { fields } => fields
+                            .iter()
+                            .map(|(name, field)| {
+                                format!(
+                                    "{}: {}",
+                                    escape(name),
+                                    self.type_of(registry, *field)
+                                )
+                            })
+                            .collect::<Vec<_>>(),
+                        SborEzModeSchemaKind::Tuple { fields } => fields
+                            .iter()
+                            .map(|&field| self.type_of(registry, field))
+                            .collect(),
+                        _ => vec![self.type_of(registry, payload)],
+                    });
+                    let fields = match fields {
+                        Some(fields) if !fields.is_empty() => {
+                            format!("<code>{}</code>", fields.join(", "))
+                        }
+                        _ => String::new(),
+                    };
+                    table.push_str(&format!(
+                        "| `{}` | {} | {} |\n",
+                        escape(&variant.name),
+                        variant.discriminator,
+                        fields
+                    ));
+                }
+                ("An enum", table)
+            }
+            SborEzModeSchemaKind::Tuple { .. } => {
+                ("A tuple", self.fields(registry, index))
+            }
+            kind => (
+                if matches!(kind, SborEzModeSchemaKind::Map { .. }) {
+                    "A map"
+                } else {
+                    "A type"
+                },
+                format!("<code>{}</code>\n", self.inline_type(registry, entry)),
+            ),
+        };
+        let description = match &entry.blueprint {
+            Some(blueprint) => {
+                format!("{} of the `{}` blueprint.", what, blueprint)
+            }
+            None => format!("{}.", what),
+        };
+        let bounds = match &entry.validation {
+            Some(validation) => {
+                format!(" Its values are {}.", validation.describe())
+            }
+            None => String::new(),
+        };
+        format!(
+            "<a id=\"{}\"></a>\n\n### `{}`\n\n{}{}\n\n{}\n",
+            anchor(name),
+            name,
+            description,
+            bounds,
+            body
+        )
+    }
+}
+
+impl Renderer for DocRenderer {
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let Selection {
+            blueprints, types, ..
+        } = registry.select(&options.filter);
+        let mut output = format!(
+            "# Package `{}`\n\nGenerated reference of the blueprints of the \
+             package and their SBOR types.\n\n",
+            package_address
+        );
+        for roots in &blueprints {
+            output.push_str(&self.blueprint(registry, roots));
+        }
+
+        // The types of the functions are documented as well.
+        let reachable = registry.reachable(
+            blueprints
+                .iter()
+                .flat_map(|roots| {
+                    roots
+                        .state
+                        .into_iter()
+                        .chain(roots.events.iter().map(|(_, index)| *index))
+                        .chain(roots.functions.iter().flat_map(|function| {
+                            [function.input, function.output]
+                        }))
+                })
+                .chain(types.iter().copied()),
+        );
+        // Variant payloads and the inputs of functions are documented where
+        // they are used. They get a section as well when anything else refers
+        // to them, so that every link has a section to go to.
+        let referenced: HashSet<u32> = blueprints
+            .iter()
+            .flat_map(|roots| {
+                roots
+                    .state
+                    .into_iter()
+                    .chain(roots.events.iter().map(|(_, index)| *index))
+                    .chain(
+                        roots.functions.iter().map(|function| function.output),
+                    )
+            })
+            .chain(types)
+            .chain(reachable.iter().flat_map(|&i| {
+                match &registry.entries[i as usize].schema_kind {
+                    SborEzModeSchemaKind::Enum { .. } => Vec::new(),
+                    kind => kind.children(),
+                }
+                .into_iter()
+                .map(|(_, child)| child)
+            }))
+            .collect();
+        let inputs: Vec<u32> = blueprints
+            .iter()
+            .flat_map(|roots| &roots.functions)
+            .map(|function| function.input)
+            .collect();
+        let payloads: Vec<u32> = reachable
+            .iter()
+            .flat_map(|&i| match &registry.entries[i as usize].schema_kind {
+                SborEzModeSchemaKind::Enum { variants } => variants
+                    .iter()
+                    .filter_map(|variant| variant.payload)
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+        let mut named: Vec<u32> = reachable
+            .into_iter()
+            .filter(|i| {
+                is_linked(&registry.entries[*i as usize])
+                    && (referenced.contains(i)
+                        || !inputs.contains(i) && !payloads.contains(i))
+            })
+            .collect();
+        named.sort_by(|a, b| {
+            registry
+                .var_name(*a)
+                .cmp(registry.var_name(*b))
+                .then(a.cmp(b))
+        });
+        if !named.is_empty() {
+            output.push_str("## Types\n\n");
+            for i in named {
+                output.push_str(&self.type_section(registry, i));
+            }
+        }
+        output.truncate(output.trim_end().len());
+        output.push('\n');
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{testing::entry, EnumVariant, ReferenceKind};
+    use crate::naming::RenameMap;
+
+    fn function(
+        name: &str,
+        receiver: Option<Receiver>,
+        visibility: Visibility,
+        input: u32,
+        output: u32,
+    ) -> FunctionRoots {
+        FunctionRoots {
+            name: name.to_string(),
+            receiver,
+            visibility,
+            input,
+            output,
+        }
+    }
+
+    #[test]
+    fn test_blueprints_link_to_their_types() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::Address {
+                    reference: ReferenceKind::Resource,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Map {
+                    key_type: 1,
+                    value_type: 0,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::InternalAddress {
+                    own: OwnKind::KeyValueStore,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("percent".to_string(), 0)],
+                },
+            ),
+            entry(
+                Some("FeeTier"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![
+                        EnumVariant {
+                            discriminator: 0,
+                            name: "Low".to_string(),
+                            payload: None,
+                        },
+                        EnumVariant {
+                            discriminator: 1,
+                            name: "Custom".to_string(),
+                            payload: Some(4),
+                        },
+                    ],
+                },
+            ),
+            entry(
+                Some("PoolState"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("reserves".to_string(), 2),
+                        ("fee_tier".to_string(), 5),
+                        ("orders".to_string(), 3),
+                    ],
+                },
+            ),
+            entry(
+                Some("SwapEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("input_resource".to_string(), 1),
+                        ("output_amount".to_string(), 0),
+                    ],
+                },
+            ),
+            entry(
+                Some("Pool_set_fee_Input"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("fee".to_string(), 5)],
+                },
+            ),
+            entry(None, SborEzModeSchemaKind::Tuple { fields: vec![] }),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(6),
+            events: vec![("SwapEvent".to_string(), 7)],
+            functions: vec![function(
+                "set_fee",
+                Some(Receiver::SelfRefMut),
+                Visibility::Roles(vec!["admin".to_string()]),
+                8,
+                9,
+            )],
+        });
+        registry.assign_names(&RenameMap::new());
+
+        let output =
+            DocRenderer.render(&registry, "package_pool", &Default::default());
+        assert_eq!(
+            output,
+            r##"# Package `package_pool`
+
+Generated reference of the blueprints of the package and their SBOR types.
+
+## `Pool` blueprint
+
+### State
+
+| Field | Type |
+| --- | --- |
+| `reserves` | <code>IndexMap&lt;ResourceAddress, Decimal&gt;</code> |
+| `fee_tier` | <code><a href="#type-FeeTier">FeeTier</a></code> |
+| `orders` | <code>KeyValueStore</code> |
+
+### Collections
+
+The fields of the state that hold many values, going by their types. This is a heuristic: collections that the blueprint declares outside of its state aren't listed.
+
+| Field | Type |
+| --- | --- |
+| `reserves` | <code>IndexMap&lt;ResourceAddress, Decimal&gt;</code> |
+| `orders` | <code>KeyValueStore</code> |
+
+### Events
+
+#### `SwapEvent`
+
+| Field | Type |
+| --- | --- |
+| `input_resource` | <code>ResourceAddress</code> |
+| `output_amount` | <code>Decimal</code> |
+
+### Functions
+
+| Signature | Callable by |
+| --- | --- |
+| <code>fn set_fee(&amp;mut self, fee: <a href="#type-FeeTier">FeeTier</a>)</code> | Roles `admin` |
+
+## Types
+
+<a id="type-FeeTier"></a>
+
+### `FeeTier`
+
+An enum of the `Pool` blueprint.
+
+| Variant | Id | Fields |
+| --- | --- | --- |
+| `Low` | 0 |  |
+| `Custom` | 1 | <code>percent: Decimal</code> |
+
+<a id="type-PoolState"></a>
+
+### `PoolState`
+
+A struct of the `Pool` blueprint.
+
+| Field | Type |
+| --- | --- |
+| `reserves` | <code>IndexMap&lt;ResourceAddress, Decimal&gt;</code> |
+| `fee_tier` | <code><a href="#type-FeeTier">FeeTier</a></code> |
+| `orders` | <code>KeyValueStore</code> |
+
+<a id="type-SwapEvent"></a>
+
+### `SwapEvent`
+
+A struct of the `Pool` blueprint.
+
+| Field | Type |
+| --- | --- |
+| `input_resource` | <code>ResourceAddress</code> |
+| `output_amount` | <code>Decimal</code> |
+"##
+        );
+    }
+
+    #[test]
+    fn test_payloads_used_elsewhere_get_a_section() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("percent".to_string(), 0)],
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("amount".to_string(), 0)],
+                },
+            ),
+            entry(
+                Some("Fee"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![
+                        EnumVariant {
+                            discriminator: 0,
+                            name: "Custom".to_string(),
+                            payload: Some(1),
+                        },
+                        EnumVariant {
+                            discriminator: 1,
+                            name: "Fixed".to_string(),
+                            payload: Some(2),
+                        },
+                    ],
+                },
+            ),
+            entry(
+                Some("FeeEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("fee".to_string(), 3),
+                        ("custom".to_string(), 1),
+                    ],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: None,
+            events: vec![("FeeEvent".to_string(), 4)],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+
+        let output =
+            DocRenderer.render(&registry, "package_pool", &Default::default());
+        let custom = registry.var_name(1);
+        assert!(output.contains(&format!(
+            "| `custom` | <code><a href=\"#{}\">{}</a></code> |",
+            anchor(custom),
+            custom
+        )));
+        assert!(output.contains(&format!("<a id=\"{}\"></a>", anchor(custom))));
+        assert!(!output.contains(&anchor(registry.var_name(2))));
+    }
+}
@@ -0,0 +1,500 @@
+//! Rendering of TypeScript types for the values that the sbor-ez-mode schemas
+//! parse into, for code that only handles parsed values, such as a frontend
+//! that gets them from a backend.
+//! The types follow the parse output exactly: decimals are strings, instants
+//! are `Date`s, and enums are unions of `{ variant, value }` objects. That
+//! includes `Option`, which parses into
+//! `{ variant: "Some"; value: T } | { variant: "None" }` rather than
+//! `T | null`. Only the fields of lenient structs can be `null`.
+
+use crate::ez_mode_gen::{
+    is_inline, BlueprintRoots, EzModeRenderer, RegistryEntry,
+    SborEzModeSchemaKind, SchemaRegistry, Selection, WideIntegerMode,
+};
+use crate::pretty::{array, separated, union, Doc};
+use crate::renderer::{RenderOptions, Renderer};
+use crate::ts_escape::{identifier, property_key, string_literal};
+use crate::well_known::WellKnownType;
+use std::collections::HashSet;
+
+/// The type that `s.value()` parses into.
+const VALUE_TYPE: &str =
+    "import('@radixdlt/babylon-gateway-api-sdk').ProgrammaticScryptoSborValue";
+
+/// Renders the registry as TypeScript types of the parsed values.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DtsRenderer;
+
+/// A type literal, from keys that are already escaped.
+fn type_literal(members: Vec<(String, Doc)>) -> Doc {
+    let items = members
+        .into_iter()
+        .map(|(key, value)| {
+            Doc::concat(vec![Doc::text(format!("{}: ", key)), value])
+        })
+        .collect();
+    separated("{", items, ";", "}", true)
+}
+
+/// A member of the union of an enum, without a value for unit variants.
+fn variant(name: &str, value: Option<Doc>) -> Doc {
+    let mut members =
+        vec![("variant".to_string(), Doc::text(string_literal(name)))];
+    if let Some(value) = value {
+        members.push(("value".to_string(), value));
+    }
+    type_literal(members)
+}
+
+/// The type of a well known type, either inline or as a reference to its
+/// helper.
+fn well_known_type(well_known: WellKnownType) -> Doc {
+    match well_known {
+        WellKnownType::Instant => Doc::text("Date"),
+        WellKnownType::Hash
+        | WellKnownType::Secp256k1PublicKey
+        | WellKnownType::Ed25519PublicKey
+        | WellKnownType::Secp256k1PublicKeyHash
+        | WellKnownType::Ed25519PublicKeyHash => Doc::text("string"),
+        WellKnownType::UtcDateTime
+        | WellKnownType::NonFungibleGlobalId
+        | WellKnownType::PublicKey
+        | WellKnownType::PublicKeyHash => Doc::text(well_known.name()),
+    }
+}
+
+/// The type of the helper of a well known type, which is named like the
+/// sbor-ez-mode helper.
+fn well_known_helper_type(well_known: WellKnownType) -> Option<Doc> {
+    let key_variant =
+        |name: &str| variant(name, Some(array(vec![Doc::text("string")])));
+    match well_known {
+        WellKnownType::UtcDateTime => Some(type_literal(
+            ["year", "month", "day_of_month", "hour", "minute", "second"]
+                .into_iter()
+                .map(|field| (field.to_string(), Doc::text("number")))
+                .collect(),
+        )),
+        WellKnownType::NonFungibleGlobalId => {
+            Some(array(vec![Doc::text("string"), Doc::text("string")]))
+        }
+        WellKnownType::PublicKey | WellKnownType::PublicKeyHash => {
+            Some(union(vec![
+                key_variant("Secp256k1"),
+                key_variant("Ed25519"),
+            ]))
+        }
+        _ => None,
+    }
+}
+
+impl DtsRenderer {
+    /// Lay the entry out as the type of its parsed values.
+    pub fn type_doc(
+        &self,
+        registry: &SchemaRegistry,
+        entry: &RegistryEntry,
+        options: &RenderOptions,
+    ) -> Doc {
+        let child = |index: u32| self.reference_doc(registry, index, options);
+        match &entry.schema_kind {
+            SborEzModeSchemaKind::Address { .. }
+            | SborEzModeSchemaKind::InternalAddress { .. }
+            | SborEzModeSchemaKind::String
+            | SborEzModeSchemaKind::NonFungibleLocalId
+            | SborEzModeSchemaKind::Bytes => Doc::text("string"),
+            // Decimals refer to the string aliases that document their
+            // precision.
+            SborEzModeSchemaKind::Decimal
+            | SborEzModeSchemaKind::PreciseDecimal => {
+                Doc::text(entry.schema_kind.decimal_precision().unwrap().name)
+            }
+            SborEzModeSchemaKind::Integer { bits, .. } => {
+                Doc::text(match (*bits > 32, &options.wide_integers) {
+                    (true, WideIntegerMode::String) => "string",
+                    (true, WideIntegerMode::BigInt) => "bigint",
+                    _ => "number",
+                })
+            }
+            SborEzModeSchemaKind::Bool => Doc::text("boolean"),
+            SborEzModeSchemaKind::WellKnown(well_known) => {
+                well_known_type(*well_known)
+            }
+            SborEzModeSchemaKind::Value => Doc::text(VALUE_TYPE),
+            SborEzModeSchemaKind::Array { element_type } => {
+                // `A | B[]` would be an array of `B`s only.
+                let element = &registry.entries[*element_type as usize];
+                if matches!(
+                    element.schema_kind,
+                    SborEzModeSchemaKind::Option { .. }
+                        | SborEzModeSchemaKind::Result { .. }
+                ) {
+                    Doc::concat(vec![
+                        Doc::text("Array<"),
+                        child(*element_type),
+                        Doc::text(">"),
+                    ])
+                } else {
+                    Doc::concat(vec![child(*element_type), Doc::text("[]")])
+                }
+            }
+            SborEzModeSchemaKind::Map {
+                key_type,
+                value_type,
+            } => Doc::concat(vec![
+                Doc::text("Map<"),
+                child(*key_type),
+                Doc::text(", "),
+                child(*value_type),
+                Doc::text(">"),
+            ]),
+            SborEzModeSchemaKind::Tuple { fields } => {
+                array(fields.iter().map(|&field| child(field)).collect())
+            }
+            SborEzModeSchemaKind::Struct { fields } => {
+                type_literal(self.fields(registry, entry, fields, options))
+            }
+            SborEzModeSchemaKind::Enum { variants } => {
+                let mut members: Vec<Doc> = variants
+                    .iter()
+                    .map(|v| variant(&v.name, v.payload.map(child)))
+                    .collect();
+                // Unknown variants are parsed with `s.value()`.
+                if registry.is_lenient(entry, options) {
+                    members.push(type_literal(vec![
+                        ("variant".to_string(), Doc::text("string")),
+                        ("value".to_string(), Doc::text(VALUE_TYPE)),
+                    ]));
+                }
+                union(members)
+            }
+            SborEzModeSchemaKind::Option { some } => union(vec![
+                variant("Some", Some(child(*some))),
+                variant("None", None),
+            ]),
+            SborEzModeSchemaKind::Result { ok, err } => union(vec![
+                variant("Ok", Some(array(vec![child(*ok)]))),
+                variant("Err", Some(array(vec![child(*err)]))),
+            ]),
+        }
+    }
+
+    /// Renders a reference to another entry, either inline or by its name.
+    fn reference_doc(
+        &self,
+        registry: &SchemaRegistry,
+        index: u32,
+        options: &RenderOptions,
+    ) -> Doc {
+        let entry = &registry.entries[index as usize];
+        if is_inline(entry) {
+            self.type_doc(registry, entry, options)
+        } else {
+            Doc::text(registry.var_name(index))
+        }
+    }
+
+    /// The members of a struct. Missing fields of lenient structs are parsed
+    /// as `null`.
+    fn fields(
+        &self,
+        registry: &SchemaRegistry,
+        entry: &RegistryEntry,
+        fields: &[(String, u32)],
+        options: &RenderOptions,
+    ) -> Vec<(String, Doc)> {
+        let lenient = registry.is_lenient(entry, options);
+        fields
+            .iter()
+            .map(|(name, field)| {
+                let field = self.reference_doc(registry, *field, options);
+                let field = if lenient {
+                    union(vec![field, Doc::text("null")])
+                } else {
+                    field
+                };
+                (property_key(name), field)
+            })
+            .collect()
+    }
+
+    /// Declares an interface with a member per line.
+    fn interface(
+        &self,
+        name: &str,
+        members: Vec<(String, Doc)>,
+        options: &RenderOptions,
+    ) -> String {
+        let export = if options.module { "export " } else { "" };
+        if members.is_empty() {
+            return format!("{}interface {} {{}}\n\n", export, name);
+        }
+        let indent = " ".repeat(options.pretty.indent_width);
+        let members: String = members
+            .into_iter()
+            .map(|(key, value)| {
+                let member = Doc::concat(vec![
+                    Doc::text(format!("{}: ", key)),
+                    value,
+                    Doc::text(";"),
+                ]);
+                format!(
+                    "{}{}\n",
+                    indent,
+                    member.print_nested(1, &options.pretty)
+                )
+            })
+            .collect();
+        format!("{}interface {} {{\n{}}}\n\n", export, name, members)
+    }
+
+    /// Declares a type alias.
+    fn alias(&self, name: &str, value: Doc, options: &RenderOptions) -> String {
+        let declaration = Doc::concat(vec![
+            Doc::text(format!(
+                "{}type {} = ",
+                if options.module { "export " } else { "" },
+                name
+            )),
+            value,
+            Doc::text(";"),
+        ]);
+        format!("{}\n\n", declaration.print(&options.pretty))
+    }
+
+    /// Declares the types of the selected entries, the helpers of the well
+    /// known types they use, and an interface per blueprint with the types
+    /// of its state and events. Every type is named like its sbor-ez-mode
+    /// schema, so the declarations can sit next to the schemas.
+    /// The decimal aliases are left out, as the schemas declare them too.
+    pub fn declarations(
+        &self,
+        registry: &SchemaRegistry,
+        blueprints: &[BlueprintRoots],
+        types: &[u32],
+        reachable: &HashSet<u32>,
+        options: &RenderOptions,
+    ) -> String {
+        let mut output = String::new();
+        let mut well_known: Vec<WellKnownType> = reachable
+            .iter()
+            .filter_map(|&i| match registry.entries[i as usize].schema_kind {
+                SborEzModeSchemaKind::WellKnown(well_known) => Some(well_known),
+                _ => None,
+            })
+            .collect();
+        well_known.sort_by_key(|well_known| well_known.name());
+        well_known.dedup();
+        for well_known in well_known {
+            if let Some(helper) = well_known_helper_type(well_known) {
+                output.push_str(&self.alias(
+                    well_known.name(),
+                    helper,
+                    options,
+                ));
+            }
+        }
+
+        let sorted_indices = registry
+            .topologically_sorted_indices()
+            .unwrap_or_else(|err| {
+                panic!("Error performing topological sort: {}", err);
+            });
+        for i in sorted_indices {
+            let entry = &registry.entries[i as usize];
+            // Inline types are only declared when they are selected by name.
+            if !reachable.contains(&i)
+                || (is_inline(entry) && !types.contains(&i))
+            {
+                continue;
+            }
+            output.push_str(&EzModeRenderer.render_doc_comment(registry, i));
+            output.push_str(&match &entry.schema_kind {
+                SborEzModeSchemaKind::Struct { fields } => self.interface(
+                    registry.var_name(i),
+                    self.fields(registry, entry, fields, options),
+                    options,
+                ),
+                _ => self.alias(
+                    registry.var_name(i),
+                    self.type_doc(registry, entry, options),
+                    options,
+                ),
+            });
+        }
+
+        for roots in blueprints {
+            let mut members = Vec::new();
+            if let Some(state) = roots.state {
+                members.push((
+                    "state".to_string(),
+                    self.reference_doc(registry, state, options),
+                ));
+            }
+            let events = roots
+                .events
+                .iter()
+                .map(|(name, index)| {
+                    (
+                        property_key(name),
+                        self.reference_doc(registry, *index, options),
+                    )
+                })
+                .collect();
+            members.push(("events".to_string(), type_literal(events)));
+            output.push_str(&self.interface(
+                &identifier(&roots.blueprint),
+                members,
+                options,
+            ));
+        }
+        output
+    }
+}
+
+impl Renderer for DtsRenderer {
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let Selection {
+            blueprints,
+            types,
+            reachable,
+        } = registry.select(&options.filter);
+        let mut output = format!(
+            "// Generated TypeScript types of the parsed Scrypto SBOR values of package address: {}\n\n{}{}",
+            package_address,
+            EzModeRenderer.render_decimal_aliases(
+                registry,
+                &reachable,
+                options.module
+            ),
+            self.declarations(
+                registry,
+                &blueprints,
+                &types,
+                &reachable,
+                options
+            )
+        );
+        output.truncate(output.trim_end().len());
+        output.push('\n');
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{testing::entry, EnumVariant, LenientConfig};
+    use crate::naming::RenameMap;
+
+    #[test]
+    fn test_types_match_the_parse_output() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::WellKnown(WellKnownType::Instant),
+            ),
+            entry(None, SborEzModeSchemaKind::Option { some: 1 }),
+            entry(None, SborEzModeSchemaKind::Array { element_type: 2 }),
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 64,
+                    signed: false,
+                },
+            ),
+            entry(
+                Some("Fill"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("amount".to_string(), 0)],
+                },
+            ),
+            entry(
+                Some("Status"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![
+                        EnumVariant {
+                            discriminator: 0,
+                            name: "Open".to_string(),
+                            payload: None,
+                        },
+                        EnumVariant {
+                            discriminator: 1,
+                            name: "Filled".to_string(),
+                            payload: Some(5),
+                        },
+                    ],
+                },
+            ),
+            entry(
+                Some("Order"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("status".to_string(), 6),
+                        ("expiries".to_string(), 3),
+                        ("nonce".to_string(), 4),
+                    ],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(7),
+            events: vec![("OrderFilled".to_string(), 5)],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+
+        let output = DtsRenderer.render(
+            &registry,
+            "package_orders",
+            &RenderOptions {
+                module: true,
+                wide_integers: WideIntegerMode::BigInt,
+                lenient: LenientConfig {
+                    lenient: HashSet::from(["Status".to_string()]),
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        assert_eq!(
+            output,
+            r#"// Generated TypeScript types of the parsed Scrypto SBOR values of package address: package_orders
+
+/** A Decimal with 18 decimal places, parsed as a string to keep its precision */
+export type Decimal = string;
+
+export interface Fill {
+    amount: Decimal;
+}
+
+export type Status =
+    | { variant: "Open" }
+    | { variant: "Filled"; value: Fill }
+    | {
+        variant: string;
+        value: import('@radixdlt/babylon-gateway-api-sdk').ProgrammaticScryptoSborValue;
+    };
+
+export interface Order {
+    status: Status;
+    expiries: Array<{ variant: "Some"; value: Date } | { variant: "None" }>;
+    nonce: bigint;
+}
+
+export interface Pool {
+    state: Order;
+    events: { OrderFilled: Fill };
+}
+"#
+        );
+    }
+}
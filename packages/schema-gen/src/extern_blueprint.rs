@@ -0,0 +1,329 @@
+//! Rendering of `extern_blueprint!` declarations, for calling the blueprints
+//! of a package from another package. The arguments and return values are
+//! declared as Rust types next to it, see [`crate::rust_gen`].
+//! The declarations have to be pasted into the `#[blueprint]` module, which
+//! picks up the package address as a dependency from the macro.
+
+use crate::ez_mode_gen::{
+    BlueprintRoots, FunctionRoots, SborEzModeSchemaKind, SchemaRegistry,
+};
+use crate::gateway::{Receiver, Visibility};
+use crate::pretty::{list, Doc};
+use crate::renderer::{RenderOptions, Renderer};
+use crate::rust_gen::{rust_identifier, RustRenderer};
+use std::collections::HashSet;
+
+/// Renders `extern_blueprint!` declarations for the functions and methods
+/// that other packages can call.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ExternBlueprintRenderer;
+
+/// Whether another package can call the function, possibly with a badge.
+fn is_callable(function: &FunctionRoots) -> bool {
+    function.visibility != Visibility::Private
+}
+
+impl ExternBlueprintRenderer {
+    /// The arguments of a function, named after the fields of its input.
+    fn arguments(
+        &self,
+        types: &RustRenderer,
+        registry: &SchemaRegistry,
+        input: u32,
+    ) -> Vec<(String, String)> {
+        match &registry.entries[input as usize].schema_kind {
+            SborEzModeSchemaKind::Struct { fields } => fields
+                .iter()
+                .map(|(name, field)| {
+                    (rust_identifier(name), types.type_of(registry, *field))
+                })
+                .collect(),
+            SborEzModeSchemaKind::Tuple { fields } => fields
+                .iter()
+                .enumerate()
+                .map(|(i, &field)| {
+                    (format!("arg{}", i), types.type_of(registry, field))
+                })
+                .collect(),
+            _ => vec![("input".to_string(), types.type_of(registry, input))],
+        }
+    }
+
+    fn function(
+        &self,
+        types: &RustRenderer,
+        registry: &SchemaRegistry,
+        function: &FunctionRoots,
+        options: &RenderOptions,
+    ) -> String {
+        let indent = " ".repeat(2 * options.pretty.indent_width);
+        let requirement = match &function.visibility {
+            Visibility::Roles(roles) if !roles.is_empty() => Some(format!(
+                "Requires one of the roles {}.",
+                roles
+                    .iter()
+                    .map(|role| format!("`{}`", role))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )),
+            Visibility::Roles(_) => {
+                Some("Requires a role of the component.".to_string())
+            }
+            Visibility::AccessRule => {
+                Some("Requires a proof for an access rule.".to_string())
+            }
+            Visibility::Public | Visibility::Private => None,
+        };
+
+        let receiver = function.receiver.map(|receiver| match receiver {
+            Receiver::SelfRef => "&self".to_string(),
+            Receiver::SelfRefMut => "&mut self".to_string(),
+        });
+        let arguments = receiver
+            .into_iter()
+            .chain(
+                self.arguments(types, registry, function.input)
+                    .into_iter()
+                    .map(|(name, argument)| format!("{}: {}", name, argument)),
+            )
+            .map(Doc::text)
+            .collect();
+        let output = types.type_of(registry, function.output);
+        let signature = Doc::concat(vec![
+            Doc::text(format!("fn {}", rust_identifier(&function.name))),
+            list("(", arguments, ")", false),
+            Doc::text(if output == "()" {
+                ";".to_string()
+            } else {
+                format!(" -> {};", output)
+            }),
+        ]);
+
+        let mut rendered = String::new();
+        if let Some(requirement) = requirement {
+            rendered.push_str(&format!("{}/// {}\n", indent, requirement));
+        }
+        rendered.push_str(&format!(
+            "{}{}\n",
+            indent,
+            signature.print_nested(2, &options.pretty)
+        ));
+        rendered
+    }
+
+    fn blueprint(
+        &self,
+        types: &RustRenderer,
+        registry: &SchemaRegistry,
+        roots: &BlueprintRoots,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let indent = " ".repeat(options.pretty.indent_width);
+        let functions: String = roots
+            .functions
+            .iter()
+            .filter(|function| is_callable(function))
+            .map(|function| self.function(types, registry, function, options))
+            .collect();
+        format!(
+            "extern_blueprint! {{\n{indent}\"{}\",\n{indent}{} {{\n{}{indent}}}\n}}\n\n",
+            package_address,
+            rust_identifier(&roots.blueprint),
+            functions,
+        )
+    }
+}
+
+impl Renderer for ExternBlueprintRenderer {
+    /// Declare the types of the arguments and return values of the callable
+    /// functions, followed by an `extern_blueprint!` per blueprint.
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let blueprints = registry.select(&options.filter).blueprints;
+        let types = RustRenderer {
+            engine_types: true,
+            extern_blueprints: blueprints
+                .iter()
+                .map(|roots| roots.blueprint.clone())
+                .collect(),
+        };
+        // The inputs are rendered as arguments, so only their fields are
+        // declared.
+        let reachable = registry.reachable(
+            blueprints
+                .iter()
+                .flat_map(|roots| &roots.functions)
+                .filter(|function| is_callable(function))
+                .flat_map(|function| {
+                    registry.entries[function.input as usize]
+                        .schema_kind
+                        .children()
+                        .into_iter()
+                        .map(|(_, child)| child)
+                        .chain([function.output])
+                }),
+        );
+
+        let mut output = format!(
+            "// Generated extern_blueprint! declarations for package address: {}\n// Paste these into your `#[blueprint]` module.\n\n{}",
+            package_address,
+            types.declarations(registry, &reachable, &HashSet::new(), options)
+        );
+        for roots in &blueprints {
+            output.push_str(&self.blueprint(
+                &types,
+                registry,
+                roots,
+                package_address,
+                options,
+            ));
+        }
+        output.truncate(output.trim_end().len());
+        output.push('\n');
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{
+        testing::entry, EnumVariant, OwnKind, ReferenceKind,
+    };
+    use crate::naming::RenameMap;
+
+    fn function(
+        name: &str,
+        receiver: Option<Receiver>,
+        visibility: Visibility,
+        input: u32,
+        output: u32,
+    ) -> FunctionRoots {
+        FunctionRoots {
+            name: name.to_string(),
+            receiver,
+            visibility,
+            input,
+            output,
+        }
+    }
+
+    #[test]
+    fn test_callable_functions_are_declared() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(
+                None,
+                SborEzModeSchemaKind::Address {
+                    reference: ReferenceKind::Resource,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Address {
+                    reference: ReferenceKind::GlobalTyped {
+                        package: None,
+                        blueprint: "Pool".to_string(),
+                    },
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::InternalAddress {
+                    own: OwnKind::Bucket,
+                },
+            ),
+            entry(
+                Some("FeeTier"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![EnumVariant {
+                        discriminator: 0,
+                        name: "Low".to_string(),
+                        payload: None,
+                    }],
+                },
+            ),
+            entry(
+                Some("Pool_instantiate_Input"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("owner_badge".to_string(), 0)],
+                },
+            ),
+            entry(
+                Some("Pool_swap_Input"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("input".to_string(), 2)],
+                },
+            ),
+            entry(
+                Some("Pool_set_fee_Input"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("fee".to_string(), 3)],
+                },
+            ),
+            entry(None, SborEzModeSchemaKind::Tuple { fields: vec![] }),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: None,
+            events: Vec::new(),
+            functions: vec![
+                function("instantiate", None, Visibility::Public, 4, 1),
+                function(
+                    "set_fee",
+                    Some(Receiver::SelfRefMut),
+                    Visibility::Roles(vec!["admin".to_string()]),
+                    6,
+                    7,
+                ),
+                function(
+                    "swap",
+                    Some(Receiver::SelfRefMut),
+                    Visibility::Public,
+                    5,
+                    2,
+                ),
+                function(
+                    "collect_fees",
+                    Some(Receiver::SelfRefMut),
+                    Visibility::Private,
+                    7,
+                    2,
+                ),
+            ],
+        });
+        registry.assign_names(&RenameMap::new());
+
+        let output = ExternBlueprintRenderer.render(
+            &registry,
+            "package_pool",
+            &RenderOptions::default(),
+        );
+        assert_eq!(
+            output,
+            r#"// Generated extern_blueprint! declarations for package address: package_pool
+// Paste these into your `#[blueprint]` module.
+
+#[derive(ScryptoSbor)]
+pub enum FeeTier {
+    Low,
+}
+
+extern_blueprint! {
+    "package_pool",
+    Pool {
+        fn instantiate(owner_badge: ResourceAddress) -> Global<Pool>;
+        /// Requires one of the roles `admin`.
+        fn set_fee(&mut self, fee: FeeTier);
+        fn swap(&mut self, input: Bucket) -> Bucket;
+    }
+}
+"#
+        );
+    }
+}
@@ -1,11 +1,24 @@
-use crate::gateway::BlueprintWithSchema;
-use radix_common::data::scrypto::well_known_scrypto_custom_types;
+use crate::dts::DtsRenderer;
+use crate::gateway::{BlueprintWithSchema, Receiver, Type, Visibility};
+use crate::naming::{assign_names, rename_warnings, RenameMap};
+use crate::pretty::{array, call, object, Doc};
+use crate::renderer::{RenderOptions, Renderer, RootFilter};
+use crate::ts_escape::{comment, identifier, property_key, string_literal};
+use crate::well_known::{self, Recognized, WellKnownType};
+use radix_common::address::AddressBech32Encoder;
+use radix_common::data::scrypto::{
+    OwnValidation, ReferenceValidation, ScryptoCustomTypeValidation,
+};
+use radix_common::network::NetworkDefinition;
+use radix_common::types::PackageAddress;
+use sbor::basic_well_known_types::BYTES_TYPE;
 use sbor::prelude::indexmap::IndexMap;
-use sbor::{LocalTypeId, LocalTypeKind, Schema, TypeKind, TypeMetadata};
+use sbor::{
+    LocalTypeId, LocalTypeKind, Schema, TypeKind, TypeMetadata, TypeValidation,
+};
 use scrypto::prelude::{
     scrypto_decode, ScryptoCustomSchema, ScryptoCustomTypeKind,
 };
-use std::borrow::Cow;
 use std::collections::HashSet;
 
 /// This module is responsible for generating sbor-ez-mode TypeScript
@@ -16,19 +29,22 @@ use std::collections::HashSet;
 /// representation can be rendered out to TypeScript code.
 
 /// Whether a RegistryEntry can be rendered inline
-fn is_inline(entry: &RegistryEntry) -> bool {
+pub(crate) fn is_inline(entry: &RegistryEntry) -> bool {
     match entry.schema_kind {
-        SborEzModeSchemaKind::Address
-        | SborEzModeSchemaKind::InternalAddress
-        | SborEzModeSchemaKind::Number
+        SborEzModeSchemaKind::Address { .. }
+        | SborEzModeSchemaKind::InternalAddress { .. }
+        | SborEzModeSchemaKind::Integer { .. }
         | SborEzModeSchemaKind::String
         | SborEzModeSchemaKind::Bool
         | SborEzModeSchemaKind::Decimal
+        | SborEzModeSchemaKind::PreciseDecimal
         | SborEzModeSchemaKind::NonFungibleLocalId
         | SborEzModeSchemaKind::Array { .. }
         | SborEzModeSchemaKind::Option { .. }
+        | SborEzModeSchemaKind::Result { .. }
         | SborEzModeSchemaKind::Value
-        | SborEzModeSchemaKind::Instant
+        | SborEzModeSchemaKind::WellKnown(_)
+        | SborEzModeSchemaKind::Bytes
         | SborEzModeSchemaKind::Map { .. } => true,
         // Anonymous tuples should be rendered inline, while named tuples should be rendered separately.
         SborEzModeSchemaKind::Tuple { .. } => entry.type_name.is_none(),
@@ -37,7 +53,7 @@ fn is_inline(entry: &RegistryEntry) -> bool {
 }
 
 /// A registry entry representing a type in sbor-ez-mode
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub struct RegistryEntry {
     // a "hash" of the type, used to compare it to other types
     pub type_hash: TypeHash,
@@ -46,143 +62,29 @@ pub struct RegistryEntry {
     // The target type in sbor-ez-mode
     pub schema_kind: SborEzModeSchemaKind,
     /// A set of dependency indices of the types this type depends on.
+    #[serde(serialize_with = "serialize_sorted")]
     pub dependencies: HashSet<u32>,
+    /// The blueprint in which this type was first encountered.
+    pub blueprint: Option<String>,
+    /// Bounds on the values of this type, from the sbor type validation.
+    pub validation: Option<Validation>,
 }
 
 impl RegistryEntry {
-    /// Returns a unique variable name based on the type and its index in the registry if the type name is not unique.
-    pub fn unique_var_name(&self, registry: &SchemaRegistry) -> String {
-        let position =
-            registry.entries.iter().position(|e| e == self).unwrap_or(0);
-        match &self.type_name {
-            Some(name) => {
-                if registry.is_type_name_unique(name) {
-                    name.to_string()
-                } else {
-                    format!("{}_{}", name, position)
-                }
-            }
-            None => {
-                format!("Type{}", position)
-            }
-        }
-    }
-
-    /// Render the entry into a TypeScript expression.
-    pub fn render(&self, registry: &SchemaRegistry) -> String {
-        match &self.schema_kind {
-            SborEzModeSchemaKind::Address => "s.address()".to_string(),
-            SborEzModeSchemaKind::Map {
-                key_type,
-                value_type,
-            } => {
-                let key_entry = &registry.entries[*key_type as usize];
-                let value_entry = &registry.entries[*value_type as usize];
-                format!(
-                    "s.map({{ key: {}, value: {} }})",
-                    if is_inline(key_entry) {
-                        key_entry.render(registry)
-                    } else {
-                        key_entry.unique_var_name(registry)
-                    },
-                    if is_inline(value_entry) {
-                        value_entry.render(registry)
-                    } else {
-                        value_entry.unique_var_name(registry)
-                    }
-                )
-            }
-            SborEzModeSchemaKind::String => "s.string()".to_string(),
-            SborEzModeSchemaKind::Decimal => "s.decimal()".to_string(),
-            SborEzModeSchemaKind::InternalAddress => {
-                "s.internalAddress()".to_string()
-            }
-            SborEzModeSchemaKind::Number => "s.number()".to_string(),
-            SborEzModeSchemaKind::Bool => "s.bool()".to_string(),
-            SborEzModeSchemaKind::Instant => "s.instant()".to_string(),
-            SborEzModeSchemaKind::NonFungibleLocalId => {
-                "s.nonFungibleLocalId()".to_string()
-            }
-            SborEzModeSchemaKind::Value => "s.value()".to_string(),
-            SborEzModeSchemaKind::Array { element_type } => {
-                let entry = &registry.entries[*element_type as usize];
-                format!(
-                    "s.array({})",
-                    if is_inline(entry) {
-                        entry.render(registry)
-                    } else {
-                        entry.unique_var_name(registry)
-                    }
-                )
-            }
-            SborEzModeSchemaKind::Tuple { fields } => {
-                let field_entries: Vec<String> = fields
-                    .iter()
-                    .map(|&field_type| {
-                        let entry = &registry.entries[field_type as usize];
-                        if is_inline(entry) {
-                            entry.render(registry)
-                        } else {
-                            entry.unique_var_name(registry)
-                        }
-                    })
-                    .collect();
-                format!("s.tuple([{}])", field_entries.join(", "))
-            }
-            SborEzModeSchemaKind::Struct { fields } => {
-                let field_entries: Vec<String> = fields
-                    .iter()
-                    .map(|(name, field_type)| {
-                        let entry = &registry.entries[*field_type as usize];
-                        if is_inline(entry) {
-                            format!("{}: {}", name, entry.render(registry))
-                        } else {
-                            format!(
-                                "{}: {}",
-                                name,
-                                entry.unique_var_name(registry)
-                            )
-                        }
-                    })
-                    .collect();
-                format!("s.struct({{\n  {}\n}})", field_entries.join(",\n  "))
-            }
-            SborEzModeSchemaKind::Enum { variants } => {
-                let variant_entries: Vec<String> = variants
-                    .iter()
-                    .map(|(name, variant_type)| {
-                        let entry = &registry.entries[*variant_type as usize];
-                        format!(
-                            "  {{ variant: \"{}\", schema: {} }}",
-                            name,
-                            if is_inline(entry) {
-                                entry.render(registry)
-                            } else {
-                                entry.unique_var_name(registry)
-                            }
-                        )
-                    })
-                    .collect();
-                format!("s.enum([\n{}\n])", variant_entries.join(",\n"))
-            }
-            SborEzModeSchemaKind::Option { inner_types } => {
-                let inner_entries: Vec<String> = inner_types
-                    .iter()
-                    .map(|&inner_type| {
-                        let entry = &registry.entries[inner_type as usize];
-                        if is_inline(entry) {
-                            entry.render(registry)
-                        } else {
-                            entry.unique_var_name(registry)
-                        }
-                    })
-                    .collect();
-                if inner_entries.len() == 1 {
-                    format!("s.option({})", inner_entries[0])
-                } else {
-                    format!("s.option(s.tuple([{}]))", inner_entries.join(", "))
-                }
-            }
+    /// Creates an entry for a sbor type, taking the hash and name from its metadata.
+    pub fn new(
+        metadata: &TypeMetadata,
+        kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
+        schema_kind: SborEzModeSchemaKind,
+        dependencies: HashSet<u32>,
+    ) -> Self {
+        Self {
+            type_hash: TypeHash::create(metadata, kind),
+            type_name: metadata.get_name().map(|s| s.to_string()),
+            schema_kind,
+            dependencies,
+            blueprint: None,
+            validation: None,
         }
     }
 }
@@ -195,7 +97,18 @@ impl PartialEq for RegistryEntry {
 
 impl Eq for RegistryEntry {}
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+/// Serializes a set of indices in ascending order, so that the IR of a
+/// package is the same on every run.
+fn serialize_sorted<S: serde::Serializer>(
+    set: &HashSet<u32>,
+    serializer: S,
+) -> Result<S::Ok, S::Error> {
+    let mut sorted: Vec<u32> = set.iter().copied().collect();
+    sorted.sort();
+    serde::Serialize::serialize(&sorted, serializer)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct TypeHash(pub String);
 
 impl TypeHash {
@@ -208,59 +121,577 @@ impl TypeHash {
         let string = format!("{:?}-{:?}", metadata, kind);
         Self(string)
     }
+
+    /// Types with the same metadata and kind can still differ in their
+    /// validation, such as a `Global<Account>` and a `Global<Pool>`.
+    pub fn with_validation(
+        self,
+        validation: Option<&TypeValidation<ScryptoCustomTypeValidation>>,
+    ) -> Self {
+        match validation {
+            None | Some(TypeValidation::None) => self,
+            Some(validation) => Self(format!("{}-{:?}", self.0, validation)),
+        }
+    }
+
+    /// The hash of the payload of an enum variant, which is registered as a
+    /// separate type next to the enum itself.
+    pub fn variant(
+        metadata: &TypeMetadata,
+        kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
+        variant_id: u8,
+    ) -> Self {
+        let Self(enum_hash) = Self::create(metadata, kind);
+        Self(format!("{}-variant-{}", enum_hash, variant_id))
+    }
 }
 
 /// Represents the types of sbor-ez-mode schema constructors that we have available.
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value")]
 pub enum SborEzModeSchemaKind {
     Struct { fields: Vec<(String, u32)> },
     Tuple { fields: Vec<u32> },
     Array { element_type: u32 },
     Map { key_type: u32, value_type: u32 },
-    Enum { variants: Vec<(String, u32)> },
-    Option { inner_types: Vec<u32> },
+    Enum { variants: Vec<EnumVariant> },
+    // The `Some` payload, which is a tuple for an `Option<(A, B)>`.
+    Option { some: u32 },
+    Result { ok: u32, err: u32 },
     Decimal,
-    Address,
-    InternalAddress,
-    Number,
+    PreciseDecimal,
+    Address { reference: ReferenceKind },
+    InternalAddress { own: OwnKind },
+    Integer { bits: u8, signed: bool },
     String,
     Bool,
-    Instant,
     NonFungibleLocalId,
     Value,
+    // A `Vec<u8>` or `[u8; N]`, which shows up as hex in programmatic JSON.
+    Bytes,
+    // A type from the table in [`crate::well_known`].
+    WellKnown(WellKnownType),
+}
+
+/// A variant of an enum, which is identified by its discriminator in SBOR.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct EnumVariant {
+    pub discriminator: u8,
+    /// The name from the type metadata, or `Variant{discriminator}` if the
+    /// metadata doesn't name the variant.
+    pub name: String,
+    /// The payload type, or None for unit variants without fields.
+    pub payload: Option<u32>,
+}
+
+/// The fixed point representation of a decimal type, so renderers can pick a
+/// numeric type that holds every value, such as `NUMERIC(77, 36)` in SQL.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DecimalPrecision {
+    /// The name of the type in Scrypto.
+    pub name: &'static str,
+    /// The width of the underlying integer.
+    pub bits: u16,
+    /// The number of digits after the decimal point.
+    pub decimal_places: u8,
+    /// The number of significant digits needed to hold every value.
+    pub total_digits: u8,
+}
+
+impl DecimalPrecision {
+    pub const DECIMAL: Self = Self {
+        name: "Decimal",
+        bits: 192,
+        decimal_places: 18,
+        total_digits: 58,
+    };
+    pub const PRECISE_DECIMAL: Self = Self {
+        name: "PreciseDecimal",
+        bits: 256,
+        decimal_places: 36,
+        total_digits: 77,
+    };
+}
+
+/// Bounds on the values of a type, taken from its sbor `TypeValidation`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value")]
+pub enum Validation {
+    /// The length of a string, or the number of elements in an array or map.
+    Length { min: Option<u32>, max: Option<u32> },
+    /// The value of an integer. Bounds are kept as strings, so that 128 bit
+    /// integers fit as well.
+    Numeric {
+        min: Option<String>,
+        max: Option<String>,
+    },
+}
+
+impl Validation {
+    fn from_sbor(
+        validation: &TypeValidation<ScryptoCustomTypeValidation>,
+    ) -> Option<Self> {
+        let validation = match validation {
+            TypeValidation::I8(v) => Self::numeric(v.min, v.max),
+            TypeValidation::I16(v) => Self::numeric(v.min, v.max),
+            TypeValidation::I32(v) => Self::numeric(v.min, v.max),
+            TypeValidation::I64(v) => Self::numeric(v.min, v.max),
+            TypeValidation::I128(v) => Self::numeric(v.min, v.max),
+            TypeValidation::U8(v) => Self::numeric(v.min, v.max),
+            TypeValidation::U16(v) => Self::numeric(v.min, v.max),
+            TypeValidation::U32(v) => Self::numeric(v.min, v.max),
+            TypeValidation::U64(v) => Self::numeric(v.min, v.max),
+            TypeValidation::U128(v) => Self::numeric(v.min, v.max),
+            TypeValidation::String(v)
+            | TypeValidation::Array(v)
+            | TypeValidation::Map(v) => Validation::Length {
+                min: v.min,
+                max: v.max,
+            },
+            _ => return None,
+        };
+        match &validation {
+            Validation::Length {
+                min: None,
+                max: None,
+            }
+            | Validation::Numeric {
+                min: None,
+                max: None,
+            } => None,
+            _ => Some(validation),
+        }
+    }
+
+    fn numeric<T: ToString>(min: Option<T>, max: Option<T>) -> Self {
+        Validation::Numeric {
+            min: min.map(|min| min.to_string()),
+            max: max.map(|max| max.to_string()),
+        }
+    }
+
+    /// A human readable description of the bounds.
+    pub fn describe(&self) -> String {
+        let (what, min, max) = match self {
+            Validation::Length { min, max } => (
+                "length ",
+                min.map(|min| min.to_string()),
+                max.map(|max| max.to_string()),
+            ),
+            Validation::Numeric { min, max } => ("", min.clone(), max.clone()),
+        };
+        match (min, max) {
+            (Some(min), Some(max)) => {
+                format!("{}between {} and {}", what, min, max)
+            }
+            (Some(min), None) => format!("{}at least {}", what, min),
+            (None, Some(max)) => format!("{}at most {}", what, max),
+            (None, None) => format!("{}unbounded", what),
+        }
+    }
+}
+
+/// What a reference points to, according to its `ReferenceValidation`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value")]
+pub enum ReferenceKind {
+    Any,
+    Global,
+    Package,
+    Component,
+    Resource,
+    /// A global object of a specific blueprint, such as an `Account`.
+    GlobalTyped {
+        package: Option<String>,
+        blueprint: String,
+    },
+    Internal,
+    InternalTyped {
+        package: Option<String>,
+        blueprint: String,
+    },
+}
+
+impl ReferenceKind {
+    fn from_validation(validation: Option<&ReferenceValidation>) -> Self {
+        match validation {
+            None => ReferenceKind::Any,
+            Some(ReferenceValidation::IsGlobal) => ReferenceKind::Global,
+            Some(ReferenceValidation::IsGlobalPackage) => {
+                ReferenceKind::Package
+            }
+            Some(ReferenceValidation::IsGlobalComponent) => {
+                ReferenceKind::Component
+            }
+            Some(ReferenceValidation::IsGlobalResourceManager) => {
+                ReferenceKind::Resource
+            }
+            Some(ReferenceValidation::IsGlobalTyped(package, blueprint)) => {
+                ReferenceKind::GlobalTyped {
+                    package: package.as_ref().map(encode_package_address),
+                    blueprint: blueprint.clone(),
+                }
+            }
+            Some(ReferenceValidation::IsInternal) => ReferenceKind::Internal,
+            Some(ReferenceValidation::IsInternalTyped(package, blueprint)) => {
+                ReferenceKind::InternalTyped {
+                    package: package.as_ref().map(encode_package_address),
+                    blueprint: blueprint.clone(),
+                }
+            }
+        }
+    }
+
+    /// A short description of the reference, mirroring the Scrypto type name.
+    pub fn describe(&self) -> Option<String> {
+        match self {
+            ReferenceKind::Any => None,
+            ReferenceKind::Global => Some("GlobalAddress".to_string()),
+            ReferenceKind::Package => Some("PackageAddress".to_string()),
+            ReferenceKind::Component => Some("ComponentAddress".to_string()),
+            ReferenceKind::Resource => Some("ResourceAddress".to_string()),
+            ReferenceKind::GlobalTyped { package, blueprint } => {
+                Some(describe_typed("Global", package, blueprint))
+            }
+            ReferenceKind::Internal => Some("InternalAddress".to_string()),
+            ReferenceKind::InternalTyped { package, blueprint } => {
+                Some(describe_typed("Internal", package, blueprint))
+            }
+        }
+    }
+}
+
+/// What an owned entity is, according to its `OwnValidation`.
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "kind", content = "value")]
+pub enum OwnKind {
+    Any,
+    Bucket,
+    Proof,
+    Vault,
+    KeyValueStore,
+    GlobalAddressReservation,
+    /// An owned object of a specific blueprint.
+    Typed {
+        package: Option<String>,
+        blueprint: String,
+    },
+}
+
+impl OwnKind {
+    fn from_validation(validation: Option<&OwnValidation>) -> Self {
+        match validation {
+            None => OwnKind::Any,
+            Some(OwnValidation::IsBucket) => OwnKind::Bucket,
+            Some(OwnValidation::IsProof) => OwnKind::Proof,
+            Some(OwnValidation::IsVault) => OwnKind::Vault,
+            Some(OwnValidation::IsKeyValueStore) => OwnKind::KeyValueStore,
+            Some(OwnValidation::IsGlobalAddressReservation) => {
+                OwnKind::GlobalAddressReservation
+            }
+            Some(OwnValidation::IsTypedObject(package, blueprint)) => {
+                OwnKind::Typed {
+                    package: package.as_ref().map(encode_package_address),
+                    blueprint: blueprint.clone(),
+                }
+            }
+        }
+    }
+
+    /// A short description of the owned entity, mirroring the Scrypto type name.
+    pub fn describe(&self) -> Option<String> {
+        match self {
+            OwnKind::Any => None,
+            OwnKind::Bucket => Some("Bucket".to_string()),
+            OwnKind::Proof => Some("Proof".to_string()),
+            OwnKind::Vault => Some("Vault".to_string()),
+            OwnKind::KeyValueStore => Some("KeyValueStore".to_string()),
+            OwnKind::GlobalAddressReservation => {
+                Some("GlobalAddressReservation".to_string())
+            }
+            OwnKind::Typed { package, blueprint } => {
+                Some(describe_typed("Owned", package, blueprint))
+            }
+        }
+    }
 }
 
-/// The registry collects generated types.
-#[derive(Default, Debug)]
+fn describe_typed(
+    wrapper: &str,
+    package: &Option<String>,
+    blueprint: &str,
+) -> String {
+    match package {
+        Some(package) => format!("{}<{}> of {}", wrapper, blueprint, package),
+        None => format!("{}<{}>", wrapper, blueprint),
+    }
+}
+
+/// Encodes a package address in its mainnet bech32 form, falling back to hex.
+fn encode_package_address(package: &PackageAddress) -> String {
+    AddressBech32Encoder::new(&NetworkDefinition::mainnet())
+        .encode(package.as_ref())
+        .unwrap_or_else(|_| hex::encode(package.as_ref()))
+}
+
+/// Render an entry of the variant list of `s.enum`.
+fn render_variant(name: &str, id: u8, schema: Option<Doc>) -> Doc {
+    let mut entries = vec![
+        ("variant".to_string(), Doc::text(string_literal(name))),
+        ("id".to_string(), Doc::text(id.to_string())),
+    ];
+    if let Some(schema) = schema {
+        entries.push(("schema".to_string(), schema));
+    }
+    object(entries)
+}
+
+/// Render a well known type, either inline or as a reference to its helper.
+fn render_well_known(well_known: WellKnownType) -> Doc {
+    match well_known {
+        WellKnownType::Instant => Doc::text("s.instant()"),
+        WellKnownType::Hash
+        | WellKnownType::Secp256k1PublicKey
+        | WellKnownType::Ed25519PublicKey
+        | WellKnownType::Secp256k1PublicKeyHash
+        | WellKnownType::Ed25519PublicKeyHash => {
+            Doc::text(format!("s.bytes() /* {} */", well_known.name()))
+        }
+        WellKnownType::UtcDateTime
+        | WellKnownType::NonFungibleGlobalId
+        | WellKnownType::PublicKey
+        | WellKnownType::PublicKeyHash => Doc::text(well_known.name()),
+    }
+}
+
+/// The definition of the helper a well known type is rendered as, if it
+/// is too large to render inline. Helpers are named after the type.
+pub(crate) fn well_known_helper(well_known: WellKnownType) -> Option<Doc> {
+    let number = || Doc::text("s.number()");
+    let key_variant = |name: &str, id: u8| {
+        let schema = call("s.tuple", vec![array(vec![Doc::text("s.bytes()")])]);
+        render_variant(name, id, Some(schema))
+    };
+    match well_known {
+        WellKnownType::UtcDateTime => Some(call(
+            "s.struct",
+            vec![object(
+                ["year", "month", "day_of_month", "hour", "minute", "second"]
+                    .into_iter()
+                    .map(|field| (field.to_string(), number()))
+                    .collect(),
+            )],
+        )),
+        WellKnownType::NonFungibleGlobalId => Some(call(
+            "s.tuple",
+            vec![array(vec![
+                Doc::text("s.address() /* ResourceAddress */"),
+                Doc::text("s.nonFungibleLocalId()"),
+            ])],
+        )),
+        WellKnownType::PublicKey | WellKnownType::PublicKeyHash => Some(call(
+            "s.enum",
+            vec![array(vec![
+                key_variant("Secp256k1", 0),
+                key_variant("Ed25519", 1),
+            ])],
+        )),
+        _ => None,
+    }
+}
+
+impl SborEzModeSchemaKind {
+    /// The precision of a decimal kind, or None for any other kind.
+    pub fn decimal_precision(&self) -> Option<DecimalPrecision> {
+        match self {
+            SborEzModeSchemaKind::Decimal => Some(DecimalPrecision::DECIMAL),
+            SborEzModeSchemaKind::PreciseDecimal => {
+                Some(DecimalPrecision::PRECISE_DECIMAL)
+            }
+            _ => None,
+        }
+    }
+
+    /// The types referenced by this kind, labeled by where they are used.
+    pub fn children(&self) -> Vec<(String, u32)> {
+        match self {
+            SborEzModeSchemaKind::Struct { fields } => fields.clone(),
+            SborEzModeSchemaKind::Tuple { fields } => fields
+                .iter()
+                .enumerate()
+                .map(|(i, &field)| (i.to_string(), field))
+                .collect(),
+            SborEzModeSchemaKind::Array { element_type } => {
+                vec![("Item".to_string(), *element_type)]
+            }
+            SborEzModeSchemaKind::Map {
+                key_type,
+                value_type,
+            } => vec![
+                ("Key".to_string(), *key_type),
+                ("Value".to_string(), *value_type),
+            ],
+            SborEzModeSchemaKind::Enum { variants } => variants
+                .iter()
+                .filter_map(|variant| {
+                    variant
+                        .payload
+                        .map(|payload| (variant.name.clone(), payload))
+                })
+                .collect(),
+            SborEzModeSchemaKind::Option { some } => {
+                vec![("Some".to_string(), *some)]
+            }
+            SborEzModeSchemaKind::Result { ok, err } => {
+                vec![("Ok".to_string(), *ok), ("Err".to_string(), *err)]
+            }
+            _ => Vec::new(),
+        }
+    }
+}
+
+/// How integers wider than 32 bits are rendered. These don't fit into a
+/// JavaScript number without losing precision.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum WideIntegerMode {
+    /// Render as `s.number()`, losing precision above 2^53.
+    #[default]
+    Number,
+    /// Render as `s.integerString()`, parsing into a decimal string.
+    String,
+    /// Render as `s.bigint()`, parsing into a bigint.
+    #[value(name = "bigint")]
+    BigInt,
+}
+
+/// Which types are rendered leniently, so that values from a newer version
+/// of a blueprint still parse. Lenient structs allow missing fields, and
+/// lenient enums parse unknown variants with `s.value()`.
+/// Types are selected by their generated name.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct LenientConfig {
+    /// Render every type leniently, except the ones in `strict`.
+    pub all: bool,
+    pub lenient: HashSet<String>,
+    pub strict: HashSet<String>,
+}
+
+impl LenientConfig {
+    pub fn is_lenient(&self, name: &str) -> bool {
+        !self.strict.contains(name) && (self.all || self.lenient.contains(name))
+    }
+}
+
+/// The root types of a blueprint: its state and the events it emits.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct BlueprintRoots {
+    pub blueprint: String,
+    /// The registry index of the state type.
+    pub state: Option<u32>,
+    /// The registry indices of the events, keyed by the event name from the
+    /// blueprint interface.
+    pub events: Vec<(String, u32)>,
+    pub functions: Vec<FunctionRoots>,
+}
+
+/// A function or method of a blueprint, with the registry indices of its
+/// input and output types.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct FunctionRoots {
+    pub name: String,
+    /// The receiver of a method, or None for a function.
+    pub receiver: Option<Receiver>,
+    pub visibility: Visibility,
+    /// A struct with a field for every argument.
+    pub input: u32,
+    pub output: u32,
+}
+
+/// The part of a registry that is rendered, see [`SchemaRegistry::select`].
+#[derive(Clone, Debug)]
+pub struct Selection {
+    /// The selected blueprints, without their state or events if those are
+    /// filtered out.
+    pub blueprints: Vec<BlueprintRoots>,
+    /// The entries that are selected by name.
+    pub types: Vec<u32>,
+    /// The entries that are reachable from the blueprints and types.
+    pub reachable: HashSet<u32>,
+}
+
+/// The registry collects generated types. It is serialized as part of the
+/// JSON IR, see [`crate::ir`].
+#[derive(Default, Debug, serde::Serialize, serde::Deserialize)]
 pub struct SchemaRegistry {
     /// Entries are stored in the order they were registered.
     pub entries: Vec<RegistryEntry>,
+    /// The root types of every blueprint, in the order they were registered.
+    pub roots: Vec<BlueprintRoots>,
+    /// The variable name of each entry, see [`crate::naming`].
+    pub names: Vec<String>,
+    /// The blueprint whose types are currently being registered.
+    #[serde(skip)]
+    pub current_blueprint: Option<String>,
 }
 
 impl SchemaRegistry {
     pub fn new() -> Self {
         Self {
             entries: Vec::new(),
+            roots: Vec::new(),
+            names: Vec::new(),
+            current_blueprint: None,
         }
     }
 
-    pub fn has_type(
+    /// (Re)computes the variable names of all entries, applying the renames.
+    pub fn assign_names(&mut self, renames: &RenameMap) {
+        self.names = assign_names(self, renames);
+    }
+
+    /// Warnings about the renames that can't be applied, see
+    /// [`rename_warnings`].
+    pub fn rename_warnings(&self, renames: &RenameMap) -> Vec<String> {
+        rename_warnings(self, renames)
+    }
+
+    /// The variable name of the entry at the given index.
+    pub fn var_name(&self, index: u32) -> &str {
+        &self.names[index as usize]
+    }
+
+    /// Whether an entry is rendered leniently, based on its variable name.
+    pub fn is_lenient(
         &self,
-        kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
-        metadata: &TypeMetadata,
+        entry: &RegistryEntry,
+        options: &RenderOptions,
     ) -> bool {
-        self.entries
+        let name = self
+            .entries
             .iter()
-            .any(|entry| entry.type_hash == TypeHash::create(metadata, kind))
+            .position(|e| e == entry)
+            .and_then(|index| self.names.get(index));
+        match name {
+            Some(name) => options.lenient.is_lenient(name),
+            None => options.lenient.all,
+        }
     }
 
-    pub fn is_type_name_unique(&self, type_name: &str) -> bool {
-        let occurrences = self
-            .entries
+    pub fn has_type(&self, type_hash: &TypeHash) -> bool {
+        self.entries
             .iter()
-            .filter(|entry| entry.type_name == Some(type_name.to_string()))
-            .count();
-        occurrences <= 1
+            .any(|entry| &entry.type_hash == type_hash)
     }
 
     /// Manually register a type. This can be used if the type does not map exactly
@@ -275,7 +706,9 @@ impl SchemaRegistry {
         {
             return i as u32;
         }
-        self.entries.push(entry.clone());
+        let mut entry = entry.clone();
+        entry.blueprint = self.current_blueprint.clone();
+        self.entries.push(entry);
         (self.entries.len() - 1) as u32
     }
 
@@ -287,20 +720,26 @@ impl SchemaRegistry {
         kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
         type_id: LocalTypeId,
     ) -> (&RegistryEntry, u32) {
-        if self.has_type(kind, metadata) {
+        let type_validation = schema.resolve_type_validation(type_id);
+        let type_hash =
+            TypeHash::create(metadata, kind).with_validation(type_validation);
+        if self.has_type(&type_hash) {
             let entry = self
                 .entries
                 .iter()
-                .find(|e| e.type_hash == TypeHash::create(metadata, kind))
+                .find(|e| e.type_hash == type_hash)
                 .unwrap();
             let pos = self
                 .entries
                 .iter()
-                .position(|e| e.type_hash == TypeHash::create(metadata, kind))
+                .position(|e| e.type_hash == type_hash)
                 .unwrap() as u32;
             (entry, pos)
         } else {
-            let entry = create_entry(self, schema, metadata, kind, type_id);
+            let mut entry = create_entry(self, schema, metadata, kind, type_id);
+            entry.type_hash = type_hash;
+            entry.validation = type_validation.and_then(Validation::from_sbor);
+            entry.blueprint = self.current_blueprint.clone();
             self.entries.push(entry);
             let pos = self.entries.len() as u32 - 1;
             (self.entries.last().unwrap(), pos)
@@ -349,31 +788,600 @@ impl SchemaRegistry {
         }
     }
 
+    /// Warnings for every rendered place where a 64 or 128 bit integer is
+    /// rendered as a JavaScript number, which loses precision above 2^53.
+    pub fn lossy_integer_warnings(
+        &self,
+        options: &RenderOptions,
+    ) -> Vec<String> {
+        if options.wide_integers != WideIntegerMode::Number {
+            return Vec::new();
+        }
+        let wide = |index: u32| match self.entries[index as usize].schema_kind {
+            SborEzModeSchemaKind::Integer { bits, signed } if bits > 32 => {
+                Some(format!("{}{}", if signed { "i" } else { "u" }, bits))
+            }
+            _ => None,
+        };
+        let Selection {
+            blueprints,
+            types,
+            reachable,
+        } = self.select(&options.filter);
+
+        // Roots that are integers themselves, and then integer fields.
+        let mut warnings: Vec<String> = blueprints
+            .iter()
+            .flat_map(|roots| {
+                roots
+                    .state
+                    .into_iter()
+                    .chain(roots.events.iter().map(|(_, index)| *index))
+            })
+            .chain(types)
+            .filter_map(|root| {
+                wide(root).map(|integer| {
+                    format!(
+                        "{} is a {}, which is rendered as a lossy number",
+                        self.var_name(root),
+                        integer
+                    )
+                })
+            })
+            .collect();
+        let mut reachable: Vec<u32> = reachable.into_iter().collect();
+        reachable.sort();
+        for i in reachable {
+            let kind = &self.entries[i as usize].schema_kind;
+            for (label, child) in kind.children() {
+                if let Some(integer) = wide(child) {
+                    warnings.push(format!(
+                        "{}.{} is a {}, which is rendered as a lossy number",
+                        self.var_name(i),
+                        label,
+                        integer
+                    ));
+                }
+            }
+        }
+        warnings
+    }
+
+    /// Warnings for the type names selected by the filter that don't match
+    /// any entry.
+    pub fn unknown_type_warnings(&self, filter: &RootFilter) -> Vec<String> {
+        let (_, unknown_types) = self.selected_types(filter);
+        unknown_types
+            .into_iter()
+            .map(|name| format!("there is no type named {}", name))
+            .collect()
+    }
+
+    /// The helpers of the well known types in the registry, by name.
+    pub(crate) fn well_known_helpers(&self) -> Vec<(&'static str, Doc)> {
+        let mut well_known: Vec<WellKnownType> = self
+            .entries
+            .iter()
+            .filter_map(|entry| match entry.schema_kind {
+                SborEzModeSchemaKind::WellKnown(well_known) => Some(well_known),
+                _ => None,
+            })
+            .collect();
+        well_known.sort_by_key(|well_known| well_known.name());
+        well_known.dedup();
+        well_known
+            .into_iter()
+            .filter_map(|well_known| {
+                well_known_helper(well_known)
+                    .map(|helper| (well_known.name(), helper))
+            })
+            .collect()
+    }
+
+    /// The blueprint roots that pass the filter, without their state or
+    /// events if those are filtered out.
+    fn selected_roots(&self, filter: &RootFilter) -> Vec<BlueprintRoots> {
+        self.roots
+            .iter()
+            .filter(|roots| filter.includes_blueprint(&roots.blueprint))
+            .map(|roots| BlueprintRoots {
+                blueprint: roots.blueprint.clone(),
+                state: if filter.events_only {
+                    None
+                } else {
+                    roots.state
+                },
+                events: if filter.state_only {
+                    Vec::new()
+                } else {
+                    roots.events.clone()
+                },
+                functions: roots.functions.clone(),
+            })
+            .collect()
+    }
+
+    /// The entries selected by their generated name, and the names that
+    /// didn't match any entry.
+    fn selected_types(&self, filter: &RootFilter) -> (Vec<u32>, Vec<String>) {
+        let selected = (0..self.entries.len() as u32)
+            .filter(|&i| filter.types.contains(self.var_name(i)))
+            .collect();
+        let mut unknown: Vec<String> = filter
+            .types
+            .iter()
+            .filter(|name| !self.names.contains(name))
+            .cloned()
+            .collect();
+        unknown.sort();
+        (selected, unknown)
+    }
+
+    /// The roots selected by the filter, and the entries reachable from them.
+    /// Selected type names that don't exist are left out, see
+    /// [`SchemaRegistry::unknown_type_warnings`].
+    pub fn select(&self, filter: &RootFilter) -> Selection {
+        let blueprints = self.selected_roots(filter);
+        let (types, _) = self.selected_types(filter);
+        let reachable = self.reachable(
+            blueprints
+                .iter()
+                .flat_map(|roots| {
+                    roots
+                        .state
+                        .into_iter()
+                        .chain(roots.events.iter().map(|(_, index)| *index))
+                })
+                .chain(types.iter().copied()),
+        );
+        Selection {
+            blueprints,
+            types,
+            reachable,
+        }
+    }
+
+    /// The indices of the entries that are reachable from the given roots,
+    /// including the roots themselves.
+    pub fn reachable(
+        &self,
+        roots: impl IntoIterator<Item = u32>,
+    ) -> HashSet<u32> {
+        let mut reachable = HashSet::new();
+        let mut stack: Vec<u32> = roots.into_iter().collect();
+        while let Some(index) = stack.pop() {
+            if reachable.insert(index) {
+                let kind = &self.entries[index as usize].schema_kind;
+                stack.extend(
+                    kind.children().into_iter().map(|(_, child)| child),
+                );
+            }
+        }
+        reachable
+    }
+}
+
+/// Renders the registry as sbor-ez-mode TypeScript schemas. This is the
+/// default output format.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EzModeRenderer;
+
+impl EzModeRenderer {
+    /// Render the entry into a TypeScript expression.
+    pub fn render_entry(
+        &self,
+        registry: &SchemaRegistry,
+        entry: &RegistryEntry,
+        options: &RenderOptions,
+    ) -> String {
+        self.entry_doc(registry, entry, options)
+            .print(&options.pretty)
+    }
+
+    /// Lay the entry out as a document, so it can be pretty printed as part
+    /// of a larger expression.
+    pub fn entry_doc(
+        &self,
+        registry: &SchemaRegistry,
+        entry: &RegistryEntry,
+        options: &RenderOptions,
+    ) -> Doc {
+        let child = |index: u32| self.reference_doc(registry, index, options);
+        match &entry.schema_kind {
+            SborEzModeSchemaKind::Address { reference } => {
+                match reference.describe() {
+                    Some(description) => Doc::text(format!(
+                        "s.address() /* {} */",
+                        comment(&description)
+                    )),
+                    None => Doc::text("s.address()"),
+                }
+            }
+            SborEzModeSchemaKind::Map {
+                key_type,
+                value_type,
+            } => call(
+                "s.map",
+                vec![object(vec![
+                    ("key".to_string(), child(*key_type)),
+                    ("value".to_string(), child(*value_type)),
+                ])],
+            ),
+            SborEzModeSchemaKind::String => Doc::text("s.string()"),
+            SborEzModeSchemaKind::Decimal
+            | SborEzModeSchemaKind::PreciseDecimal => {
+                let precision = entry.schema_kind.decimal_precision().unwrap();
+                Doc::text(format!(
+                    "s.decimal() /* {}, {} decimal places */",
+                    precision.name, precision.decimal_places
+                ))
+            }
+            SborEzModeSchemaKind::InternalAddress { own } => {
+                match own.describe() {
+                    Some(description) => Doc::text(format!(
+                        "s.internalAddress() /* {} */",
+                        comment(&description)
+                    )),
+                    None => Doc::text("s.internalAddress()"),
+                }
+            }
+            SborEzModeSchemaKind::Integer { bits, .. } => {
+                Doc::text(match (*bits > 32, &options.wide_integers) {
+                    (true, WideIntegerMode::String) => "s.integerString()",
+                    (true, WideIntegerMode::BigInt) => "s.bigint()",
+                    _ => "s.number()",
+                })
+            }
+            SborEzModeSchemaKind::Bool => Doc::text("s.bool()"),
+            SborEzModeSchemaKind::WellKnown(well_known) => {
+                render_well_known(*well_known)
+            }
+            SborEzModeSchemaKind::NonFungibleLocalId => {
+                Doc::text("s.nonFungibleLocalId()")
+            }
+            SborEzModeSchemaKind::Value => Doc::text("s.value()"),
+            SborEzModeSchemaKind::Bytes => Doc::text("s.bytes()"),
+            SborEzModeSchemaKind::Array { element_type } => {
+                call("s.array", vec![child(*element_type)])
+            }
+            SborEzModeSchemaKind::Tuple { fields } => call(
+                "s.tuple",
+                vec![array(fields.iter().map(|&field| child(field)).collect())],
+            ),
+            SborEzModeSchemaKind::Struct { fields } => {
+                let field_entries = fields
+                    .iter()
+                    .map(|(name, field_type)| {
+                        (property_key(name), child(*field_type))
+                    })
+                    .collect();
+                call(
+                    if registry.is_lenient(entry, options) {
+                        "s.structNullable"
+                    } else {
+                        "s.struct"
+                    },
+                    vec![object(field_entries)],
+                )
+            }
+            SborEzModeSchemaKind::Enum { variants } => {
+                let variant_entries = variants
+                    .iter()
+                    .map(|variant| {
+                        render_variant(
+                            &variant.name,
+                            variant.discriminator,
+                            variant.payload.map(child),
+                        )
+                    })
+                    .collect();
+                let mut args = vec![array(variant_entries)];
+                if registry.is_lenient(entry, options) {
+                    args.push(Doc::text("s.value()"));
+                }
+                call("s.enum", args)
+            }
+            SborEzModeSchemaKind::Option { some } => {
+                call("s.option", vec![child(*some)])
+            }
+            SborEzModeSchemaKind::Result { ok, err } => {
+                let variant = |name: &str, id: u8, payload: u32| {
+                    let schema =
+                        call("s.tuple", vec![array(vec![child(payload)])]);
+                    render_variant(name, id, Some(schema))
+                };
+                call(
+                    "s.enum",
+                    vec![array(vec![
+                        variant("Ok", 0, *ok),
+                        variant("Err", 1, *err),
+                    ])],
+                )
+            }
+        }
+    }
+
+    /// Renders a reference to another entry, either inline or by its variable name.
+    fn reference_doc(
+        &self,
+        registry: &SchemaRegistry,
+        index: u32,
+        options: &RenderOptions,
+    ) -> Doc {
+        let entry = &registry.entries[index as usize];
+        if is_inline(entry) {
+            self.entry_doc(registry, entry, options)
+        } else {
+            Doc::text(registry.var_name(index))
+        }
+    }
+
+    /// Render a JSDoc comment listing the validations of an entry and of the
+    /// types it directly refers to, or nothing if there are none.
+    pub(crate) fn render_doc_comment(
+        &self,
+        registry: &SchemaRegistry,
+        index: u32,
+    ) -> String {
+        let entry = &registry.entries[index as usize];
+        let mut lines = Vec::new();
+        if let Some(validation) = &entry.validation {
+            lines.push(format!(" * {}", comment(&validation.describe())));
+        }
+        for (label, child) in entry.schema_kind.children() {
+            if let Some(validation) =
+                &registry.entries[child as usize].validation
+            {
+                lines.push(format!(
+                    " * {}: {}",
+                    comment(&label),
+                    comment(&validation.describe())
+                ));
+            }
+        }
+        if lines.is_empty() {
+            String::new()
+        } else {
+            format!("/**\n{}\n */\n", lines.join("\n"))
+        }
+    }
+
+    /// Render the export map of a blueprint, pointing to its state and events.
+    fn render_roots(
+        &self,
+        registry: &SchemaRegistry,
+        roots: &BlueprintRoots,
+        options: &RenderOptions,
+    ) -> Doc {
+        let mut entries = Vec::new();
+        if let Some(state) = roots.state {
+            entries.push((
+                "state".to_string(),
+                self.reference_doc(registry, state, options),
+            ));
+        }
+        let events = roots
+            .events
+            .iter()
+            .map(|(name, index)| {
+                (
+                    property_key(name),
+                    self.reference_doc(registry, *index, options),
+                )
+            })
+            .collect();
+        entries.push(("events".to_string(), object(events)));
+        object(entries)
+    }
+
+    /// Render a lookup table from blueprint and event name to event schema,
+    /// together with a typed `decodeEvent` helper built on top of it.
+    fn render_event_decoder(
+        &self,
+        roots: &[BlueprintRoots],
+        options: &RenderOptions,
+    ) -> String {
+        let export = if options.module { "export " } else { "" };
+        let table_entries = roots
+            .iter()
+            .filter(|roots| !roots.events.is_empty())
+            .map(|roots| {
+                (
+                    property_key(&roots.blueprint),
+                    Doc::text(format!(
+                        "{}.events",
+                        identifier(&roots.blueprint)
+                    )),
+                )
+            })
+            .collect();
+        let table = Doc::concat(vec![
+            Doc::text(format!("{}const eventSchemas = ", export)),
+            object(table_entries),
+            Doc::text(";"),
+        ]);
+        // The template is indented by two spaces per level, and re-indented
+        // to the configured width.
+        let decoder = format!(
+            r#"type EventSchemas = typeof eventSchemas;
+type ProgrammaticJson = s.infer<ReturnType<typeof s.value>>;
+type Parsed<S> = S extends {{ parse(...args: never[]): infer T }} ? T : never;
+
+{export}type KnownEvent = {{
+  [B in keyof EventSchemas]: {{
+    [E in keyof EventSchemas[B]]: {{
+      known: true;
+      blueprint: B;
+      event: E;
+      data: Parsed<EventSchemas[B][E]>;
+    }};
+  }}[keyof EventSchemas[B]];
+}}[keyof EventSchemas];
+
+{export}type UnknownEvent = {{
+  known: false;
+  blueprint: string;
+  event: string;
+  data: ProgrammaticJson;
+}};
+
+type EventSchema = {{
+  parse(value: ProgrammaticJson, path: string[]): unknown;
+}};
+const eventTable: Record<string, Record<string, EventSchema> | undefined> =
+  eventSchemas;
+
+/**
+ * Decode the programmatic JSON payload of an event, based on the blueprint
+ * that emitted it and the name of the event. Events that are not part of this
+ * package are returned as-is.
+ * @throws {{SborError}} If the payload doesn't match the schema of the event
+ */
+{export}function decodeEvent(
+  emitterBlueprint: string,
+  eventName: string,
+  programmaticJson: ProgrammaticJson
+): KnownEvent | UnknownEvent {{
+  const schema = eventTable[emitterBlueprint]?.[eventName];
+  if (!schema) {{
+    return {{
+      known: false,
+      blueprint: emitterBlueprint,
+      event: eventName,
+      data: programmaticJson,
+    }};
+  }}
+  return {{
+    known: true,
+    blueprint: emitterBlueprint,
+    event: eventName,
+    data: schema.parse(programmaticJson, []),
+  }} as KnownEvent;
+}}
+
+"#,
+            export = export,
+        );
+        format!(
+            "{}\n\n{}",
+            table.print(&options.pretty),
+            reindent(&decoder, options.pretty.indent_width)
+        )
+    }
+
+    /// Render a string type alias for every decimal type in the registry,
+    /// documenting its precision.
+    pub(crate) fn render_decimal_aliases(
+        &self,
+        registry: &SchemaRegistry,
+        reachable: &HashSet<u32>,
+        module: bool,
+    ) -> String {
+        let mut precisions: Vec<DecimalPrecision> = reachable
+            .iter()
+            .filter_map(|&i| {
+                registry.entries[i as usize].schema_kind.decimal_precision()
+            })
+            .collect();
+        precisions.sort_by_key(|precision| precision.bits);
+        precisions.dedup();
+        precisions
+            .iter()
+            .map(|precision| {
+                format!(
+                    "/** A {} with {} decimal places, parsed as a string to keep its precision */\n{}type {} = string;\n\n",
+                    precision.name,
+                    precision.decimal_places,
+                    if module { "export " } else { "" },
+                    precision.name
+                )
+            })
+            .collect()
+    }
+}
+
+impl Renderer for EzModeRenderer {
     /// Render the registry entries in topologically sorted order.
-    pub fn render(&self, package_address: &str, module: bool) -> String {
+    /// Only the types reachable from the roots selected by the filter are
+    /// emitted.
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let module = options.module;
+        let Selection {
+            blueprints,
+            types,
+            reachable,
+        } = registry.select(&options.filter);
+        let reachable_well_known: HashSet<&str> = reachable
+            .iter()
+            .filter_map(|&i| match registry.entries[i as usize].schema_kind {
+                SborEzModeSchemaKind::WellKnown(well_known) => {
+                    Some(well_known.name())
+                }
+                _ => None,
+            })
+            .collect();
+
         let mut output = String::new();
-        let sorted_indices =
-            self.topologically_sorted_indices().unwrap_or_else(|err| {
+        let sorted_indices = registry
+            .topologically_sorted_indices()
+            .unwrap_or_else(|err| {
                 panic!("Error performing topological sort: {}", err);
             });
-        for i in sorted_indices {
-            let entry = &self.entries[i as usize];
-            if entry.type_name == Some("AvlTree".to_string()) {
-                println!("{:?}", entry);
-                println!("Index: {}", i);
+        let declaration = |name: &str, value: Doc| {
+            let declaration = Doc::concat(vec![
+                Doc::text(format!(
+                    "{}const {} = ",
+                    if module { "export " } else { "" },
+                    name
+                )),
+                value,
+                Doc::text(";"),
+            ]);
+            format!("{}\n\n", declaration.print(&options.pretty))
+        };
+        output.push_str(
+            &self.render_decimal_aliases(registry, &reachable, module),
+        );
+        for (name, helper) in registry.well_known_helpers() {
+            if reachable_well_known.contains(name) {
+                output.push_str(&declaration(name, helper));
             }
-            if entry.type_name == Some("Node".to_string()) {
-                println!("{:?}", entry);
-                println!("Index: {}", i);
-            }
-            if is_inline(entry) {
+        }
+        for i in sorted_indices {
+            let entry = &registry.entries[i as usize];
+            // Inline types are only declared when they are selected by name.
+            if !reachable.contains(&i)
+                || (is_inline(entry) && !types.contains(&i))
+            {
                 continue;
             }
-            output.push_str(&format!(
-                "{}const {} = {};\n\n",
-                if module { "export " } else { "" },
-                entry.unique_var_name(self),
-                entry.render(self)
+            output.push_str(&self.render_doc_comment(registry, i));
+            output.push_str(&declaration(
+                registry.var_name(i),
+                self.entry_doc(registry, entry, options),
+            ));
+        }
+        for roots in &blueprints {
+            output.push_str(&declaration(
+                &identifier(&roots.blueprint),
+                self.render_roots(registry, roots, options),
+            ));
+        }
+        if blueprints.iter().any(|roots| !roots.events.is_empty()) {
+            output.push_str(&self.render_event_decoder(&blueprints, options));
+        }
+        if options.with_types {
+            output.push_str(&DtsRenderer.declarations(
+                registry,
+                &blueprints,
+                &types,
+                &reachable,
+                options,
             ));
         }
 
@@ -394,6 +1402,22 @@ impl SchemaRegistry {
     }
 }
 
+/// Re-indents code that is indented by two spaces per level to the given
+/// indent width.
+pub(crate) fn reindent(code: &str, indent_width: usize) -> String {
+    code.split('\n')
+        .map(|line| {
+            let content = line.trim_start_matches(' ');
+            // Odd indents, like the ` * ` of a doc comment, keep their
+            // extra space.
+            let indent = line.len() - content.len();
+            let indent = indent / 2 * indent_width + indent % 2;
+            format!("{}{}", " ".repeat(indent), content)
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 /// Registers a type by resolving its kind and metadata.
 /// This function encapsulates the pattern of resolving a type and calling get_or_register.
 fn register_type(
@@ -442,12 +1466,12 @@ fn handle_tuple(
             .into_iter()
             .map(|(name, index, _)| (name, index))
             .collect();
-        RegistryEntry {
-            type_hash: TypeHash::create(metadata, kind),
-            type_name: metadata.get_name().map(|s| s.to_string()),
-            schema_kind: SborEzModeSchemaKind::Struct { fields },
+        RegistryEntry::new(
+            metadata,
+            kind,
+            SborEzModeSchemaKind::Struct { fields },
             dependencies,
-        }
+        )
     } else {
         // Unnamed fields: simple tuple.
         let field_entries: Vec<(RegistryEntry, u32)> = field_types
@@ -464,15 +1488,26 @@ fn handle_tuple(
             .collect();
         let indices =
             field_entries.into_iter().map(|(_, index)| index).collect();
-        RegistryEntry {
-            type_hash: TypeHash::create(metadata, kind),
-            type_name: metadata.get_name().map(|s| s.to_string()),
-            schema_kind: SborEzModeSchemaKind::Tuple { fields: indices },
+        RegistryEntry::new(
+            metadata,
+            kind,
+            SborEzModeSchemaKind::Tuple { fields: indices },
             dependencies,
-        }
+        )
     }
 }
 
+/// Whether an array type holds raw bytes, either because it is the well known
+/// `BYTES` type, or because its elements are `u8`s.
+fn is_bytes(
+    schema: &Schema<ScryptoCustomSchema>,
+    type_id: LocalTypeId,
+    element_type: LocalTypeId,
+) -> bool {
+    type_id == LocalTypeId::WellKnown(BYTES_TYPE)
+        || matches!(schema.resolve_type_kind(element_type), Some(TypeKind::U8))
+}
+
 ///
 /// Helper: Handle array types.
 ///
@@ -486,14 +1521,14 @@ fn handle_array(
     let (entry, index) = register_type(registry, schema, element_type);
     let mut dependencies = entry.dependencies.clone();
     dependencies.insert(index);
-    RegistryEntry {
-        type_hash: TypeHash::create(metadata, kind),
-        type_name: metadata.get_name().map(|s| s.to_string()),
-        schema_kind: SborEzModeSchemaKind::Array {
+    RegistryEntry::new(
+        metadata,
+        kind,
+        SborEzModeSchemaKind::Array {
             element_type: index,
         },
         dependencies,
-    }
+    )
 }
 
 ///
@@ -518,45 +1553,58 @@ fn handle_map(
     // Explicitly add direct dependency indices for key and value.
     dependencies.insert(key_index);
     dependencies.insert(value_index);
-    RegistryEntry {
-        type_hash: TypeHash::create(metadata, kind),
-        type_name: metadata.get_name().map(|s| s.to_string()),
-        schema_kind: SborEzModeSchemaKind::Map {
+    RegistryEntry::new(
+        metadata,
+        kind,
+        SborEzModeSchemaKind::Map {
             key_type: key_index,
             value_type: value_index,
         },
         dependencies,
-    }
+    )
 }
 ///
 /// Helper: Handle custom Scrypto types.
 ///
 fn handle_custom(
     custom: &ScryptoCustomTypeKind,
+    schema: &Schema<ScryptoCustomSchema>,
     metadata: &TypeMetadata,
     kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
+    type_id: LocalTypeId,
 ) -> RegistryEntry {
+    let validation = match schema.resolve_type_validation(type_id) {
+        Some(TypeValidation::Custom(validation)) => Some(validation),
+        _ => None,
+    };
     let schema_kind = match custom {
-        ScryptoCustomTypeKind::Decimal
-        | ScryptoCustomTypeKind::PreciseDecimal => {
-            SborEzModeSchemaKind::Decimal
+        ScryptoCustomTypeKind::Decimal => SborEzModeSchemaKind::Decimal,
+        ScryptoCustomTypeKind::PreciseDecimal => {
+            SborEzModeSchemaKind::PreciseDecimal
         }
-        ScryptoCustomTypeKind::Reference => SborEzModeSchemaKind::Address,
-        ScryptoCustomTypeKind::Own => SborEzModeSchemaKind::InternalAddress,
+        ScryptoCustomTypeKind::Reference => SborEzModeSchemaKind::Address {
+            reference: ReferenceKind::from_validation(match validation {
+                Some(ScryptoCustomTypeValidation::Reference(reference)) => {
+                    Some(reference)
+                }
+                _ => None,
+            }),
+        },
+        ScryptoCustomTypeKind::Own => SborEzModeSchemaKind::InternalAddress {
+            own: OwnKind::from_validation(match validation {
+                Some(ScryptoCustomTypeValidation::Own(own)) => Some(own),
+                _ => None,
+            }),
+        },
         ScryptoCustomTypeKind::NonFungibleLocalId => {
             SborEzModeSchemaKind::NonFungibleLocalId
         }
     };
-    RegistryEntry {
-        type_hash: TypeHash::create(metadata, kind),
-        type_name: metadata.get_name().map(|s| s.to_string()),
-        schema_kind,
-        dependencies: HashSet::new(),
-    }
+    RegistryEntry::new(metadata, kind, schema_kind, HashSet::new())
 }
 
 ///
-/// Helper: Handle enum types (Which can eiher be parsed to an Option or not).
+/// Helper: Handle enum types.
 ///
 fn handle_enum(
     variants: &IndexMap<u8, Vec<LocalTypeId>>,
@@ -565,69 +1613,25 @@ fn handle_enum(
     metadata: &TypeMetadata,
     kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
 ) -> RegistryEntry {
-    // Check for Option enum.
-    if metadata.type_name.as_ref() == Some(&Cow::Owned("Option".to_string())) {
-        if let Some((_, type_ids)) =
-            variants.into_iter().find(|(variant_id, _)| {
-                let variant_data = metadata.get_matching_enum_variant_data(
-                    **variant_id,
-                    variants.len(),
-                );
-                variant_data.variant_name.as_deref() == Some("Some")
-            })
-        {
-            if type_ids.len() == 1 {
-                let (entry, index) =
-                    register_type(registry, schema, type_ids[0]);
-                let mut dependencies = entry.dependencies.clone();
-                dependencies.insert(index);
-                return RegistryEntry {
-                    type_hash: TypeHash::create(metadata, kind),
-                    type_name: metadata.get_name().map(|s| s.to_string()),
-                    schema_kind: SborEzModeSchemaKind::Option {
-                        inner_types: vec![index],
-                    },
-                    dependencies,
-                };
-            } else {
-                let entries: Vec<(RegistryEntry, u32)> = type_ids
-                    .iter()
-                    .map(|&tid| register_type(registry, schema, tid))
-                    .collect();
-                let dependencies: HashSet<u32> = entries
-                    .iter()
-                    .flat_map(|(entry, index)| {
-                        let mut deps = entry.dependencies.clone();
-                        deps.insert(*index);
-                        deps.into_iter()
-                    })
-                    .collect();
-                let indices =
-                    entries.into_iter().map(|(_, index)| index).collect();
-                return RegistryEntry {
-                    type_hash: TypeHash::create(metadata, kind),
-                    type_name: metadata.get_name().map(|s| s.to_string()),
-                    schema_kind: SborEzModeSchemaKind::Option {
-                        inner_types: indices,
-                    },
-                    dependencies,
-                };
-            }
-        } else {
-            panic!("Option enum does not have a Some variant");
-        }
-    } else {
-        // Non‑option enum.
-        let variant_entries: Vec<(String, RegistryEntry, u32)> = variants
+    let variant_entries: Vec<(u8, String, Option<(RegistryEntry, u32)>)> =
+        variants
             .iter()
             .map(|(variant_id, type_ids)| {
                 let variant_data = metadata.get_matching_enum_variant_data(
                     *variant_id,
                     type_ids.len(),
                 );
-                let variant_name =
-                    variant_data.variant_name.unwrap_or("<unnamed>");
-                if let Some(field_names) = &variant_data.field_names {
+                // Without metadata, variants can only be told apart by their
+                // discriminator.
+                let variant_name = match variant_data.variant_name {
+                    Some(name) => name.to_string(),
+                    None => format!("Variant{}", variant_id),
+                };
+                // Unit variants have no payload, which sbor-ez-mode expresses by
+                // leaving out the schema of the variant.
+                if type_ids.is_empty() {
+                    (*variant_id, variant_name, None)
+                } else if let Some(field_names) = &variant_data.field_names {
                     let fields: Vec<(String, u32, RegistryEntry)> = type_ids
                         .iter()
                         .enumerate()
@@ -650,15 +1654,21 @@ fn handle_enum(
                         .map(|(name, index, _)| (name, index))
                         .collect();
                     let entry = RegistryEntry {
-                        type_hash: TypeHash::create(metadata, kind),
+                        type_hash: TypeHash::variant(
+                            metadata,
+                            kind,
+                            *variant_id,
+                        ),
                         type_name: None,
                         schema_kind: SborEzModeSchemaKind::Struct {
                             fields: fields_indices,
                         },
                         dependencies,
+                        blueprint: None,
+                        validation: None,
                     };
                     let index = registry.register(&entry);
-                    (variant_name.to_string(), entry, index)
+                    (*variant_id, variant_name, Some((entry, index)))
                 } else {
                     let entries: Vec<(RegistryEntry, u32)> = type_ids
                         .iter()
@@ -675,39 +1685,103 @@ fn handle_enum(
                     let indices =
                         entries.into_iter().map(|(_, index)| index).collect();
                     let entry = RegistryEntry {
-                        type_hash: TypeHash::create(metadata, kind),
+                        type_hash: TypeHash::variant(
+                            metadata,
+                            kind,
+                            *variant_id,
+                        ),
                         type_name: None,
                         schema_kind: SborEzModeSchemaKind::Tuple {
                             fields: indices,
                         },
                         dependencies,
+                        blueprint: None,
+                        validation: None,
                     };
                     let index = registry.register(&entry);
-                    (variant_name.to_string(), entry, index)
+                    (*variant_id, variant_name, Some((entry, index)))
                 }
             })
             .collect();
-        let dependencies: HashSet<u32> = variant_entries
-            .iter()
-            .flat_map(|(_, entry, index)| {
-                let mut deps = entry.dependencies.clone();
-                deps.insert(*index);
-                deps.into_iter()
-            })
-            .collect();
-        let variant_indices = variant_entries
-            .into_iter()
-            .map(|(name, _, index)| (name, index))
-            .collect();
-        RegistryEntry {
-            type_hash: TypeHash::create(metadata, kind),
-            type_name: metadata.get_name().map(|s| s.to_string()),
-            schema_kind: SborEzModeSchemaKind::Enum {
-                variants: variant_indices,
-            },
-            dependencies,
+    let dependencies: HashSet<u32> = variant_entries
+        .iter()
+        .filter_map(|(_, _, payload)| payload.as_ref())
+        .flat_map(|(entry, index)| {
+            let mut deps = entry.dependencies.clone();
+            deps.insert(*index);
+            deps.into_iter()
+        })
+        .collect();
+    let variants = variant_entries
+        .into_iter()
+        .map(|(discriminator, name, payload)| EnumVariant {
+            discriminator,
+            name,
+            payload: payload.map(|(_, index)| index),
+        })
+        .collect();
+    RegistryEntry::new(
+        metadata,
+        kind,
+        SborEzModeSchemaKind::Enum { variants },
+        dependencies,
+    )
+}
+
+///
+/// Helper: Handle types from the table of well known types.
+///
+fn handle_well_known(
+    recognized: Recognized,
+    registry: &mut SchemaRegistry,
+    schema: &Schema<ScryptoCustomSchema>,
+    metadata: &TypeMetadata,
+    kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
+) -> RegistryEntry {
+    let (schema_kind, children) = match recognized {
+        Recognized::WellKnown(well_known) => {
+            (SborEzModeSchemaKind::WellKnown(well_known), vec![])
         }
-    }
+        Recognized::Option { some } => {
+            let (entry, index) = register_type(registry, schema, some);
+            (
+                SborEzModeSchemaKind::Option { some: index },
+                vec![(entry, index)],
+            )
+        }
+        Recognized::Result { ok, err } => {
+            let (ok_entry, ok) = register_type(registry, schema, ok);
+            let (err_entry, err) = register_type(registry, schema, err);
+            (
+                SborEzModeSchemaKind::Result { ok, err },
+                vec![(ok_entry, ok), (err_entry, err)],
+            )
+        }
+    };
+    let dependencies: HashSet<u32> = children
+        .iter()
+        .flat_map(|(entry, index)| {
+            let mut deps = entry.dependencies.clone();
+            deps.insert(*index);
+            deps.into_iter()
+        })
+        .collect();
+    RegistryEntry::new(metadata, kind, schema_kind, dependencies)
+}
+
+/// Creates an entry for an integer, keeping its width and signedness.
+fn integer_entry(
+    metadata: &TypeMetadata,
+    kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
+    bits: u8,
+    signed: bool,
+) -> RegistryEntry {
+    RegistryEntry::new(
+        metadata,
+        kind,
+        SborEzModeSchemaKind::Integer { bits, signed },
+        HashSet::new(),
+    )
 }
 
 /// The recursive function to generate an IR registry entry from a sbor type.
@@ -718,13 +1792,27 @@ fn create_entry(
     kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
     type_id: LocalTypeId,
 ) -> RegistryEntry {
+    if let Some(recognized) = well_known::recognize(metadata, kind, type_id) {
+        return handle_well_known(recognized, registry, schema, metadata, kind);
+    }
     match kind {
         TypeKind::Tuple { field_types } => {
             handle_tuple(field_types, registry, schema, metadata, kind)
         }
-        TypeKind::Custom(custom) => handle_custom(custom, metadata, kind),
+        TypeKind::Custom(custom) => {
+            handle_custom(custom, schema, metadata, kind, type_id)
+        }
         TypeKind::Array { element_type } => {
-            handle_array(*element_type, registry, schema, metadata, kind)
+            if is_bytes(schema, type_id, *element_type) {
+                RegistryEntry::new(
+                    metadata,
+                    kind,
+                    SborEzModeSchemaKind::Bytes,
+                    HashSet::new(),
+                )
+            } else {
+                handle_array(*element_type, registry, schema, metadata, kind)
+            }
         }
         TypeKind::Enum { variants } => {
             handle_enum(variants, registry, schema, metadata, kind)
@@ -735,55 +1823,34 @@ fn create_entry(
         } => {
             handle_map(*key_type, *value_type, registry, schema, metadata, kind)
         }
-        TypeKind::Bool
-        | TypeKind::U8
-        | TypeKind::U16
-        | TypeKind::U32
-        | TypeKind::U64
-        | TypeKind::U128
-        | TypeKind::I8
-        | TypeKind::I16
-        | TypeKind::I32
-        | TypeKind::I128 => RegistryEntry {
-            type_hash: TypeHash::create(metadata, kind),
-            type_name: metadata.get_name().map(|s| s.to_string()),
-            schema_kind: SborEzModeSchemaKind::Number,
-            dependencies: HashSet::new(),
-        },
-        TypeKind::I64 => {
-            // If the integer is of the well known Instant type, we would like to use s.insant() for it.
-            if type_id
-                == LocalTypeId::WellKnown(
-                    well_known_scrypto_custom_types::INSTANT_TYPE,
-                )
-            {
-                RegistryEntry {
-                    type_hash: TypeHash::create(metadata, kind),
-                    type_name: metadata.get_name().map(|s| s.to_string()),
-                    schema_kind: SborEzModeSchemaKind::Instant,
-                    dependencies: HashSet::new(),
-                }
-            } else {
-                RegistryEntry {
-                    type_hash: TypeHash::create(metadata, kind),
-                    type_name: metadata.get_name().map(|s| s.to_string()),
-                    schema_kind: SborEzModeSchemaKind::Number,
-                    dependencies: HashSet::new(),
-                }
-            }
-        }
-        TypeKind::String => RegistryEntry {
-            type_hash: TypeHash::create(metadata, kind),
-            type_name: metadata.get_name().map(|s| s.to_string()),
-            schema_kind: SborEzModeSchemaKind::String,
-            dependencies: HashSet::new(),
-        },
-        TypeKind::Any => RegistryEntry {
-            type_hash: TypeHash::create(metadata, kind),
-            type_name: metadata.get_name().map(|s| s.to_string()),
-            schema_kind: SborEzModeSchemaKind::Value,
-            dependencies: HashSet::new(),
-        },
+        TypeKind::Bool => RegistryEntry::new(
+            metadata,
+            kind,
+            SborEzModeSchemaKind::Bool,
+            HashSet::new(),
+        ),
+        TypeKind::U8 => integer_entry(metadata, kind, 8, false),
+        TypeKind::U16 => integer_entry(metadata, kind, 16, false),
+        TypeKind::U32 => integer_entry(metadata, kind, 32, false),
+        TypeKind::U64 => integer_entry(metadata, kind, 64, false),
+        TypeKind::U128 => integer_entry(metadata, kind, 128, false),
+        TypeKind::I8 => integer_entry(metadata, kind, 8, true),
+        TypeKind::I16 => integer_entry(metadata, kind, 16, true),
+        TypeKind::I32 => integer_entry(metadata, kind, 32, true),
+        TypeKind::I128 => integer_entry(metadata, kind, 128, true),
+        TypeKind::I64 => integer_entry(metadata, kind, 64, true),
+        TypeKind::String => RegistryEntry::new(
+            metadata,
+            kind,
+            SborEzModeSchemaKind::String,
+            HashSet::new(),
+        ),
+        TypeKind::Any => RegistryEntry::new(
+            metadata,
+            kind,
+            SborEzModeSchemaKind::Value,
+            HashSet::new(),
+        ),
     }
 }
 
@@ -803,12 +1870,24 @@ fn get_type_by_index(
         })
 }
 
+/// Registers one of the root types of a blueprint, returning its index.
+fn register_root(
+    registry: &mut SchemaRegistry,
+    schema: &Schema<ScryptoCustomSchema>,
+    type_data: &Type,
+) -> Option<u32> {
+    let (metadata, _kind) = get_type_by_index(schema, type_data.type_id)?;
+    let type_id = LocalTypeId::SchemaLocalIndex(type_data.type_id as usize);
+    let kind = schema.resolve_type_kind(type_id).unwrap();
+    let (_, index) = registry.get_or_register(schema, &metadata, kind, type_id);
+    Some(index)
+}
+
 /// takesa few related schemas and generates a schema registry
 /// with all the sbor-ez-mode types in it. This can be considered a
 /// kind of intermediate representation for the schema of the package.
 pub fn generate_ir(schemas: &[BlueprintWithSchema]) -> SchemaRegistry {
     let mut registry = SchemaRegistry::new();
-    let mut main_vars = Vec::new();
     let mut schemas = schemas.to_vec();
     schemas.reverse();
 
@@ -816,32 +1895,443 @@ pub fn generate_ir(schemas: &[BlueprintWithSchema]) -> SchemaRegistry {
         let schema_deserialized: Schema<ScryptoCustomSchema> =
             scrypto_decode(&schema.schema.schema)
                 .expect("Failed to decode schema");
+        registry.current_blueprint = Some(schema.blueprint.name.clone());
 
-        let mut types = schema.blueprint.events.clone();
-        types.push(schema.blueprint.state.clone());
-
-        for type_data in types {
-            if let Some((metadata, _kind)) =
-                get_type_by_index(&schema_deserialized, type_data.type_id)
+        let mut roots = BlueprintRoots {
+            blueprint: schema.blueprint.name.clone(),
+            state: None,
+            events: Vec::new(),
+            functions: Vec::new(),
+        };
+        for event in &schema.blueprint.events {
+            if let Some(index) =
+                register_root(&mut registry, &schema_deserialized, event)
             {
-                let type_id =
-                    LocalTypeId::SchemaLocalIndex(type_data.type_id as usize);
-                let var = registry.get_or_register(
-                    &schema_deserialized,
-                    &metadata,
-                    &schema_deserialized
-                        .resolve_type_kind(LocalTypeId::SchemaLocalIndex(
-                            type_data.type_id as usize,
-                        ))
-                        .unwrap(),
-                    type_id,
-                );
-                let hash = var.0.type_hash.clone();
-                if !main_vars.contains(&hash) {
-                    main_vars.push(hash);
-                }
+                roots.events.push((event.name.clone(), index));
             }
         }
+        roots.state = register_root(
+            &mut registry,
+            &schema_deserialized,
+            &schema.blueprint.state,
+        );
+        for function in &schema.blueprint.functions {
+            let (_, input) = register_type(
+                &mut registry,
+                &schema_deserialized,
+                function.input,
+            );
+            let (_, output) = register_type(
+                &mut registry,
+                &schema_deserialized,
+                function.output,
+            );
+            roots.functions.push(FunctionRoots {
+                name: function.name.clone(),
+                receiver: function.receiver,
+                visibility: function.visibility.clone(),
+                input,
+                output,
+            });
+        }
+        registry.roots.push(roots);
     }
+    registry.current_blueprint = None;
+    registry.assign_names(&RenameMap::new());
     registry
 }
+
+/// Registry entries for the tests of the renderers.
+#[cfg(test)]
+pub(crate) mod testing {
+    use super::*;
+
+    /// An entry of the `Pool` blueprint, which depends on its children.
+    pub(crate) fn entry(
+        name: Option<&str>,
+        schema_kind: SborEzModeSchemaKind,
+    ) -> RegistryEntry {
+        RegistryEntry {
+            type_hash: TypeHash(format!("{:?}-{:?}", name, schema_kind)),
+            type_name: name.map(|s| s.to_string()),
+            dependencies: schema_kind
+                .children()
+                .into_iter()
+                .map(|(_, child)| child)
+                .collect(),
+            schema_kind,
+            blueprint: Some("Pool".to_string()),
+            validation: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::testing::entry;
+    use super::*;
+
+    fn render(entries: Vec<RegistryEntry>, index: usize) -> String {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = entries;
+        EzModeRenderer.render_entry(
+            &registry,
+            &registry.entries[index],
+            &RenderOptions::default(),
+        )
+    }
+
+    // The expected output matches the fixtures in the sbor-ez-mode tests,
+    // which parse real programmatic JSON.
+
+    #[test]
+    fn test_option_of_a_tuple() {
+        let entries = vec![
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 32,
+                    signed: false,
+                },
+            ),
+            entry(None, SborEzModeSchemaKind::String),
+            entry(None, SborEzModeSchemaKind::Tuple { fields: vec![0, 1] }),
+            entry(Some("Option"), SborEzModeSchemaKind::Option { some: 2 }),
+        ];
+        assert_eq!(
+            render(entries, 3),
+            "s.option(s.tuple([s.number(), s.string()]))"
+        );
+    }
+
+    /// The output for a package with hostile names is checked in as a
+    /// fixture of the sbor-ez-mode tests, which type check and run it. Run
+    /// with `UPDATE_FIXTURES=1` to regenerate it.
+    #[test]
+    fn test_adversarial_names_render_valid_typescript() {
+        let with_dependencies = |mut entry: RegistryEntry, deps: &[u32]| {
+            entry.dependencies = deps.iter().copied().collect();
+            entry
+        };
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::String),
+            with_dependencies(
+                entry(
+                    Some("class"),
+                    SborEzModeSchemaKind::Struct {
+                        fields: vec![
+                            ("my-field".to_string(), 0),
+                            ("constructor".to_string(), 0),
+                        ],
+                    },
+                ),
+                &[0],
+            ),
+            with_dependencies(
+                entry(
+                    Some("Vec<u8>"),
+                    SborEzModeSchemaKind::Enum {
+                        variants: vec![
+                            EnumVariant {
+                                discriminator: 0,
+                                name: "Say \"hi\"".to_string(),
+                                payload: None,
+                            },
+                            EnumVariant {
+                                discriminator: 1,
+                                name: "*/ break".to_string(),
+                                payload: Some(3),
+                            },
+                        ],
+                    },
+                ),
+                &[0, 3],
+            ),
+            with_dependencies(
+                entry(None, SborEzModeSchemaKind::Tuple { fields: vec![0] }),
+                &[0],
+            ),
+            with_dependencies(
+                entry(
+                    Some("HashMap<K, V>"),
+                    SborEzModeSchemaKind::Struct {
+                        fields: vec![
+                            ("0th".to_string(), 2),
+                            ("value".to_string(), 1),
+                        ],
+                    },
+                ),
+                &[0, 1, 2, 3],
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(4),
+            events: vec![("my-event".to_string(), 1)],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output = EzModeRenderer.render(
+            &registry,
+            "package_adversarial",
+            &RenderOptions {
+                module: true,
+                with_types: true,
+                ..Default::default()
+            },
+        );
+
+        let fixture = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../sbor-ez-mode/src/fixtures/adversarialNames.generated.ts"
+        );
+        if std::env::var_os("UPDATE_FIXTURES").is_some() {
+            std::fs::write(fixture, &output).unwrap();
+        }
+        assert_eq!(output, std::fs::read_to_string(fixture).unwrap());
+    }
+
+    #[test]
+    fn test_lenient_types_are_selected_by_name() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::String),
+            entry(
+                Some("Pool"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("name".to_string(), 0)],
+                },
+            ),
+            entry(
+                Some("Status"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![EnumVariant {
+                        discriminator: 0,
+                        name: "Open".to_string(),
+                        payload: None,
+                    }],
+                },
+            ),
+        ];
+        registry.assign_names(&RenameMap::new());
+        let options = RenderOptions {
+            lenient: LenientConfig {
+                all: true,
+                strict: HashSet::from(["Status".to_string()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            EzModeRenderer.render_entry(
+                &registry,
+                &registry.entries[1],
+                &options
+            ),
+            "s.structNullable({ name: s.string() })"
+        );
+        assert_eq!(
+            EzModeRenderer.render_entry(
+                &registry,
+                &registry.entries[2],
+                &options
+            ),
+            "s.enum([{ variant: \"Open\", id: 0 }])"
+        );
+
+        let options = RenderOptions {
+            lenient: LenientConfig {
+                lenient: HashSet::from(["Status".to_string()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        assert_eq!(
+            EzModeRenderer.render_entry(
+                &registry,
+                &registry.entries[1],
+                &options
+            ),
+            "s.struct({ name: s.string() })"
+        );
+        assert_eq!(
+            EzModeRenderer.render_entry(
+                &registry,
+                &registry.entries[2],
+                &options
+            ),
+            "s.enum([{ variant: \"Open\", id: 0 }], s.value())"
+        );
+    }
+
+    #[test]
+    fn test_unit_variants_have_no_schema() {
+        let entries = vec![
+            entry(None, SborEzModeSchemaKind::String),
+            entry(None, SborEzModeSchemaKind::Tuple { fields: vec![0] }),
+            entry(
+                Some("PoolStatus"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![
+                        EnumVariant {
+                            discriminator: 0,
+                            name: "Open".to_string(),
+                            payload: None,
+                        },
+                        EnumVariant {
+                            discriminator: 1,
+                            name: "Paused".to_string(),
+                            payload: Some(1),
+                        },
+                    ],
+                },
+            ),
+        ];
+        assert_eq!(
+            render(entries, 2),
+            "s.enum([\n    { variant: \"Open\", id: 0 },\n    { variant: \"Paused\", id: 1, schema: s.tuple([s.string()]) },\n])"
+        );
+    }
+
+    #[test]
+    fn test_only_reachable_types_are_emitted() {
+        let field = |name: &str| vec![(name.to_string(), 0)];
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::String),
+            entry(
+                Some("PoolState"),
+                SborEzModeSchemaKind::Struct {
+                    fields: field("name"),
+                },
+            ),
+            entry(
+                Some("Orphan"),
+                SborEzModeSchemaKind::Struct {
+                    fields: field("orphan"),
+                },
+            ),
+            entry(
+                Some("SwapEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: field("amount"),
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(1),
+            events: vec![("SwapEvent".to_string(), 3)],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let render = |filter: RootFilter| {
+            EzModeRenderer.render(
+                &registry,
+                "package_filtered",
+                &RenderOptions {
+                    filter,
+                    ..Default::default()
+                },
+            )
+        };
+
+        let output = render(RootFilter::default());
+        assert!(output.contains("const PoolState = "));
+        assert!(output.contains("const SwapEvent = "));
+        assert!(!output.contains("Orphan"));
+
+        let output = render(RootFilter {
+            state_only: true,
+            ..Default::default()
+        });
+        assert!(output.contains("const PoolState = "));
+        assert!(!output.contains("SwapEvent"));
+        assert!(!output.contains("decodeEvent"));
+
+        let output = render(RootFilter {
+            types: HashSet::from(["Orphan".to_string()]),
+            ..Default::default()
+        });
+        assert!(output.contains("const Orphan = "));
+        assert!(!output.contains("PoolState"));
+        assert!(!output.contains("const Pool = "));
+    }
+
+    #[test]
+    fn test_lossy_integer_warnings_cover_what_is_rendered() {
+        let amount = || vec![("amount".to_string(), 0)];
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 64,
+                    signed: false,
+                },
+            ),
+            entry(
+                Some("VolumeEvent"),
+                SborEzModeSchemaKind::Integer {
+                    bits: 128,
+                    signed: true,
+                },
+            ),
+            entry(
+                Some("PoolState"),
+                SborEzModeSchemaKind::Struct { fields: amount() },
+            ),
+            entry(
+                Some("RouterState"),
+                SborEzModeSchemaKind::Struct { fields: amount() },
+            ),
+            entry(
+                Some("SwapInput"),
+                SborEzModeSchemaKind::Struct { fields: amount() },
+            ),
+        ];
+        registry.roots = vec![
+            BlueprintRoots {
+                blueprint: "Pool".to_string(),
+                state: Some(2),
+                events: vec![("VolumeEvent".to_string(), 1)],
+                functions: vec![FunctionRoots {
+                    name: "swap".to_string(),
+                    receiver: Some(Receiver::SelfRefMut),
+                    visibility: Visibility::Public,
+                    input: 4,
+                    output: 0,
+                }],
+            },
+            BlueprintRoots {
+                blueprint: "Router".to_string(),
+                state: Some(3),
+                events: Vec::new(),
+                functions: Vec::new(),
+            },
+        ];
+        registry.assign_names(&RenameMap::new());
+        let options = RenderOptions {
+            filter: RootFilter {
+                blueprints: HashSet::from(["Pool".to_string()]),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            registry.lossy_integer_warnings(&options),
+            [
+                "VolumeEvent is a i128, which is rendered as a lossy number",
+                "PoolState.amount is a u64, which is rendered as a lossy number",
+            ]
+        );
+        assert!(registry
+            .lossy_integer_warnings(&RenderOptions {
+                wide_integers: WideIntegerMode::BigInt,
+                ..options
+            })
+            .is_empty());
+    }
+}
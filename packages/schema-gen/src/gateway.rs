@@ -1,6 +1,6 @@
 use reqwest::blocking::Client;
-use serde::Deserialize;
-use serde_json::json;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::Debug;
@@ -60,10 +60,47 @@ pub struct Type {
     pub schema_hash: String,
 }
 
+/// How a method is called on its component.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Receiver {
+    /// `&self`
+    SelfRef,
+    /// `&mut self`
+    SelfRefMut,
+}
+
+/// Who can call a function or method, according to the auth configuration
+/// of its blueprint.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", content = "value")]
+pub enum Visibility {
+    Public,
+    /// Callable by holders of one of these roles of the component.
+    Roles(Vec<String>),
+    /// Guarded by an access rule of the package.
+    AccessRule,
+    /// Only callable from inside the package, and not from other packages.
+    Private,
+}
+
+/// A function or method of a blueprint.
+#[derive(Debug, Clone)]
+pub struct Function {
+    pub name: String,
+    /// The receiver of a method, or None for a function.
+    pub receiver: Option<Receiver>,
+    pub visibility: Visibility,
+    /// A struct with a field for every argument.
+    pub input: sbor::LocalTypeId,
+    pub output: sbor::LocalTypeId,
+}
+
 #[derive(Debug, Clone)]
 pub struct BlueprintDefinition {
+    pub name: String,
     pub state: Type,
     pub events: Vec<Type>,
+    pub functions: Vec<Function>,
     pub schema_hash: String,
 }
 
@@ -76,6 +113,7 @@ struct BlueprintsResponse {
 struct BlueprintItem {
     name: String,
     definition: BlueprintDefinitionInner,
+    auth_template: Option<Value>,
 }
 
 #[derive(Deserialize)]
@@ -85,15 +123,29 @@ struct BlueprintDefinitionInner {
 
 #[derive(Deserialize)]
 struct BlueprintInterface {
-    events: Option<HashMap<String, EventValue>>,
+    events: Option<HashMap<String, PayloadValue>>,
     state: Option<StateValue>,
+    functions: Option<HashMap<String, FunctionValue>>,
 }
 
+/// The type of an event, or of the input or output of a function.
 #[derive(Deserialize)]
-struct EventValue {
+struct PayloadValue {
     type_id: TypeIdValue,
 }
 
+#[derive(Deserialize)]
+struct FunctionValue {
+    receiver: Option<ReceiverValue>,
+    input: PayloadValue,
+    output: PayloadValue,
+}
+
+#[derive(Deserialize)]
+struct ReceiverValue {
+    receiver: Receiver,
+}
+
 #[derive(Deserialize)]
 struct TypeIdValue {
     schema_hash: String,
@@ -102,9 +154,31 @@ struct TypeIdValue {
 
 #[derive(Deserialize)]
 struct LocalTypeId {
+    #[serde(default)]
+    kind: LocalTypeIdKind,
     id: u32,
 }
 
+#[derive(Default, Deserialize)]
+enum LocalTypeIdKind {
+    #[default]
+    SchemaLocal,
+    WellKnown,
+}
+
+impl LocalTypeId {
+    fn to_sbor(&self) -> sbor::LocalTypeId {
+        match self.kind {
+            LocalTypeIdKind::SchemaLocal => {
+                sbor::LocalTypeId::SchemaLocalIndex(self.id as usize)
+            }
+            LocalTypeIdKind::WellKnown => sbor::LocalTypeId::WellKnown(
+                sbor::WellKnownTypeId::of(self.id as u8),
+            ),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 struct StateValue {
     fields: FieldsContainer,
@@ -125,6 +199,50 @@ struct FieldTypeRef {
     type_id: TypeIdValue,
 }
 
+/// Reads who can call a function or method from the auth template of its
+/// blueprint, which has the shape of an `AuthConfig` of the Core API.
+/// Anything the template doesn't restrict is public.
+fn visibility(
+    auth_template: Option<&Value>,
+    name: &str,
+    receiver: Option<Receiver>,
+) -> Visibility {
+    let Some(template) = auth_template else {
+        return Visibility::Public;
+    };
+    if receiver.is_some() {
+        if template["method_auth_type"] != "StaticRoleDefinition" {
+            return Visibility::Public;
+        }
+        let accessibility = &template["method_roles"]["methods"][name];
+        match accessibility["type"].as_str() {
+            Some("RoleProtected") => Visibility::Roles(
+                accessibility["allowed_roles"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|role| role.as_str().map(str::to_string))
+                    .collect(),
+            ),
+            Some("OuterObjectOnly" | "OwnPackageOnly") => Visibility::Private,
+            _ => Visibility::Public,
+        }
+    } else {
+        match template["function_auth_type"].as_str() {
+            Some("RootOnly") => Visibility::Private,
+            Some("AccessRules") => {
+                let rule = &template["function_access_rules"][name];
+                match rule["type"].as_str() {
+                    None | Some("AllowAll") => Visibility::Public,
+                    Some("DenyAll") => Visibility::Private,
+                    Some(_) => Visibility::AccessRule,
+                }
+            }
+            _ => Visibility::Public,
+        }
+    }
+}
+
 pub fn get_blueprint_definitions(
     package_address: &str,
 ) -> Result<Vec<BlueprintDefinition>, Box<dyn Error>> {
@@ -138,12 +256,18 @@ pub fn get_blueprint_definitions(
         .send()?
         .error_for_status()?
         .json()?;
+    blueprint_definitions(response)
+}
 
+/// Reads the blueprint definitions from a response of the gateway.
+fn blueprint_definitions(
+    response: BlueprintsResponse,
+) -> Result<Vec<BlueprintDefinition>, Box<dyn Error>> {
     let mut blueprints = Vec::new();
     for item in response.items {
         let blueprint_name = item.name;
         let interface = item.definition.interface;
-        let events = if let Some(events_map) = interface.events {
+        let mut events: Vec<Type> = if let Some(events_map) = interface.events {
             events_map
                 .into_iter()
                 .map(|(event_name, event_value)| Type {
@@ -155,6 +279,31 @@ pub fn get_blueprint_definitions(
         } else {
             Vec::new()
         };
+        // The gateway returns events as a map, sort them so that the
+        // generated output does not depend on the map's iteration order.
+        events.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let mut functions: Vec<Function> = interface
+            .functions
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(function_name, function)| {
+                let receiver =
+                    function.receiver.map(|receiver| receiver.receiver);
+                Function {
+                    visibility: visibility(
+                        item.auth_template.as_ref(),
+                        &function_name,
+                        receiver,
+                    ),
+                    name: function_name,
+                    receiver,
+                    input: function.input.type_id.local_type_id.to_sbor(),
+                    output: function.output.type_id.local_type_id.to_sbor(),
+                }
+            })
+            .collect();
+        functions.sort_by(|a, b| a.name.cmp(&b.name));
 
         let state_type = if let Some(state) = interface.state {
             let fields = state.fields.fields;
@@ -173,8 +322,10 @@ pub fn get_blueprint_definitions(
         };
 
         blueprints.push(BlueprintDefinition {
+            name: blueprint_name,
             state: state_type.clone(),
             events,
+            functions,
             schema_hash: state_type.schema_hash,
         });
     }
@@ -211,6 +362,62 @@ pub fn get_blueprints_and_corresponding_schemas(
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_blueprint_definitions_are_read_from_a_response() {
+        let fixture = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/gateway_blueprints.json"
+        ));
+        let response: BlueprintsResponse =
+            serde_json::from_str(fixture).unwrap();
+        let blueprints = blueprint_definitions(response).unwrap();
+        assert_eq!(blueprints.len(), 1);
+        let pool = &blueprints[0];
+        assert_eq!(pool.name, "Pool");
+        assert_eq!(pool.state.type_id, 0);
+        let events: Vec<(&str, u32)> = pool
+            .events
+            .iter()
+            .map(|event| (event.name.as_str(), event.type_id))
+            .collect();
+        assert_eq!(events, [("FeeChangedEvent", 3), ("SwapEvent", 2)]);
+
+        let functions: Vec<(&str, Option<Receiver>, Visibility)> = pool
+            .functions
+            .iter()
+            .map(|function| {
+                (
+                    function.name.as_str(),
+                    function.receiver,
+                    function.visibility.clone(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            functions,
+            [
+                ("instantiate", None, Visibility::Public),
+                ("instantiate_with_owner", None, Visibility::AccessRule),
+                ("migrate", None, Visibility::Private),
+                ("price", Some(Receiver::SelfRef), Visibility::Public),
+                ("rebalance", Some(Receiver::SelfRefMut), Visibility::Private),
+                (
+                    "set_fee",
+                    Some(Receiver::SelfRefMut),
+                    Visibility::Roles(vec![
+                        "OWNER".to_string(),
+                        "admin".to_string()
+                    ])
+                ),
+                ("swap", Some(Receiver::SelfRefMut), Visibility::Public),
+            ]
+        );
+        assert_eq!(
+            pool.functions[0].input,
+            sbor::LocalTypeId::SchemaLocalIndex(8)
+        );
+    }
+
     #[test]
     fn test_get_schema() {
         let package_address = "package_rdx1pkl8tdw43xqx64etxwdf8rjtvptqurq4c3fky0kaj6vwa0zrkfmcmc";
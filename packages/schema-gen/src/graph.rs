@@ -0,0 +1,335 @@
+//! Export of the type graph of a package as a Mermaid flowchart or a
+//! Graphviz DOT digraph, for reviewing how its types depend on each other.
+//! Every reachable entry is a node, labeled with its kind and blueprint, and
+//! every reference is an edge, labeled with the field or variant it comes
+//! from. The state and event types of the blueprints are highlighted.
+
+use crate::ez_mode_gen::{
+    is_inline, SborEzModeSchemaKind, SchemaRegistry, Selection,
+};
+use crate::renderer::{RenderOptions, Renderer};
+use crate::rust_gen::RustRenderer;
+use std::collections::HashSet;
+
+/// The fill colors of the state and event types.
+const STATE_COLOR: &str = "#bfdbfe";
+const EVENT_COLOR: &str = "#fde68a";
+
+/// The syntax of the exported graph.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum GraphFormat {
+    /// A Mermaid flowchart, which renders in GitHub Markdown.
+    #[default]
+    Mermaid,
+    /// A Graphviz DOT digraph.
+    Dot,
+}
+
+/// The kind of an entry, for the label of its node.
+fn kind_name(kind: &SborEzModeSchemaKind) -> &'static str {
+    match kind {
+        SborEzModeSchemaKind::Struct { .. } => "struct",
+        SborEzModeSchemaKind::Tuple { .. } => "tuple",
+        SborEzModeSchemaKind::Array { .. } => "array",
+        SborEzModeSchemaKind::Map { .. } => "map",
+        SborEzModeSchemaKind::Enum { .. } => "enum",
+        SborEzModeSchemaKind::Option { .. } => "option",
+        SborEzModeSchemaKind::Result { .. } => "result",
+        SborEzModeSchemaKind::Decimal
+        | SborEzModeSchemaKind::PreciseDecimal => "decimal",
+        SborEzModeSchemaKind::Address { .. } => "address",
+        SborEzModeSchemaKind::InternalAddress { .. } => "own",
+        SborEzModeSchemaKind::Integer { .. } => "integer",
+        SborEzModeSchemaKind::String => "string",
+        SborEzModeSchemaKind::Bool => "bool",
+        SborEzModeSchemaKind::NonFungibleLocalId => "local id",
+        SborEzModeSchemaKind::Value => "value",
+        SborEzModeSchemaKind::Bytes => "bytes",
+        SborEzModeSchemaKind::WellKnown(_) => "well known",
+    }
+}
+
+/// Whether a node is a root of a blueprint.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Root {
+    State,
+    Event,
+}
+
+/// A node of the graph, with the lines of its label.
+struct Node {
+    index: u32,
+    name: String,
+    description: String,
+    root: Option<Root>,
+}
+
+/// Renders the type graph of the selected blueprints and types.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct GraphRenderer {
+    pub format: GraphFormat,
+}
+
+impl GraphRenderer {
+    fn mermaid(&self, nodes: &[Node], edges: &[(u32, String, u32)]) -> String {
+        let escape = |text: &str| {
+            text.replace('"', "#quot;")
+                .replace('<', "#lt;")
+                .replace('>', "#gt;")
+        };
+        let mut output = "flowchart LR\n".to_string();
+        for node in nodes {
+            output.push_str(&format!(
+                "    t{}[\"{}<br/>{}\"]\n",
+                node.index,
+                escape(&node.name),
+                escape(&node.description)
+            ));
+        }
+        for (from, label, to) in edges {
+            output.push_str(&format!(
+                "    t{} -->|\"{}\"| t{}\n",
+                from,
+                escape(label),
+                to
+            ));
+        }
+        for (root, class, color) in [
+            (Root::State, "state", STATE_COLOR),
+            (Root::Event, "event", EVENT_COLOR),
+        ] {
+            let members: Vec<String> = nodes
+                .iter()
+                .filter(|node| node.root == Some(root))
+                .map(|node| format!("t{}", node.index))
+                .collect();
+            if !members.is_empty() {
+                output.push_str(&format!(
+                    "    classDef {} fill:{}\n    class {} {}\n",
+                    class,
+                    color,
+                    members.join(","),
+                    class
+                ));
+            }
+        }
+        output
+    }
+
+    fn dot(&self, nodes: &[Node], edges: &[(u32, String, u32)]) -> String {
+        let escape =
+            |text: &str| text.replace('\\', "\\\\").replace('"', "\\\"");
+        let mut output =
+            "digraph types {\n    rankdir=LR;\n    node [shape=box];\n"
+                .to_string();
+        for node in nodes {
+            let fill = match node.root {
+                Some(Root::State) => {
+                    format!(", style=filled, fillcolor=\"{}\"", STATE_COLOR)
+                }
+                Some(Root::Event) => {
+                    format!(", style=filled, fillcolor=\"{}\"", EVENT_COLOR)
+                }
+                None => String::new(),
+            };
+            output.push_str(&format!(
+                "    t{} [label=\"{}\\n{}\"{}];\n",
+                node.index,
+                escape(&node.name),
+                escape(&node.description),
+                fill
+            ));
+        }
+        for (from, label, to) in edges {
+            output.push_str(&format!(
+                "    t{} -> t{} [label=\"{}\"];\n",
+                from,
+                to,
+                escape(label)
+            ));
+        }
+        output.push_str("}\n");
+        output
+    }
+}
+
+impl Renderer for GraphRenderer {
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        _package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let Selection {
+            blueprints,
+            reachable,
+            ..
+        } = registry.select(&options.filter);
+        let states: HashSet<u32> =
+            blueprints.iter().filter_map(|roots| roots.state).collect();
+        let events: HashSet<u32> = blueprints
+            .iter()
+            .flat_map(|roots| roots.events.iter().map(|(_, index)| *index))
+            .collect();
+
+        // Inline types have no name of their own, and are labeled with
+        // their Scrypto type instead.
+        let types = RustRenderer::default();
+        let mut indices: Vec<u32> = reachable.into_iter().collect();
+        indices.sort();
+        let nodes: Vec<Node> = indices
+            .iter()
+            .map(|&index| {
+                let entry = &registry.entries[index as usize];
+                let kind = kind_name(&entry.schema_kind);
+                Node {
+                    index,
+                    name: if is_inline(entry) {
+                        types.type_of(registry, index)
+                    } else {
+                        registry.var_name(index).to_string()
+                    },
+                    description: match &entry.blueprint {
+                        Some(blueprint) => format!("{} · {}", kind, blueprint),
+                        None => kind.to_string(),
+                    },
+                    root: if states.contains(&index) {
+                        Some(Root::State)
+                    } else if events.contains(&index) {
+                        Some(Root::Event)
+                    } else {
+                        None
+                    },
+                }
+            })
+            .collect();
+        let edges: Vec<(u32, String, u32)> = indices
+            .iter()
+            .flat_map(|&index| {
+                registry.entries[index as usize]
+                    .schema_kind
+                    .children()
+                    .into_iter()
+                    .map(move |(label, child)| (index, label, child))
+            })
+            .collect();
+
+        match self.format {
+            GraphFormat::Mermaid => self.mermaid(&nodes, &edges),
+            GraphFormat::Dot => self.dot(&nodes, &edges),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{testing::entry, BlueprintRoots, EnumVariant};
+    use crate::naming::RenameMap;
+
+    fn registry() -> SchemaRegistry {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                Some("FeeTier"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![
+                        EnumVariant {
+                            discriminator: 0,
+                            name: "Low".to_string(),
+                            payload: None,
+                        },
+                        EnumVariant {
+                            discriminator: 1,
+                            name: "Custom".to_string(),
+                            payload: Some(2),
+                        },
+                    ],
+                },
+            ),
+            entry(None, SborEzModeSchemaKind::Tuple { fields: vec![0] }),
+            entry(
+                Some("PoolState"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("fee_tier".to_string(), 1)],
+                },
+            ),
+            entry(
+                Some("SwapEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("amount".to_string(), 0)],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(3),
+            events: vec![("SwapEvent".to_string(), 4)],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        registry
+    }
+
+    #[test]
+    fn test_graph_renders_as_mermaid() {
+        let output = GraphRenderer {
+            format: GraphFormat::Mermaid,
+        }
+        .render(&registry(), "package_pool", &Default::default());
+        assert_eq!(
+            output,
+            r##"flowchart LR
+    t0["Decimal<br/>decimal · Pool"]
+    t1["FeeTier<br/>enum · Pool"]
+    t2["(Decimal,)<br/>tuple · Pool"]
+    t3["PoolState<br/>struct · Pool"]
+    t4["SwapEvent<br/>struct · Pool"]
+    t1 -->|"Custom"| t2
+    t2 -->|"0"| t0
+    t3 -->|"fee_tier"| t1
+    t4 -->|"amount"| t0
+    classDef state fill:#bfdbfe
+    class t3 state
+    classDef event fill:#fde68a
+    class t4 event
+"##
+        );
+    }
+
+    #[test]
+    fn test_graph_renders_as_dot() {
+        let output = GraphRenderer {
+            format: GraphFormat::Dot,
+        }
+        .render(&registry(), "package_pool", &Default::default());
+        assert_eq!(
+            output,
+            r##"digraph types {
+    rankdir=LR;
+    node [shape=box];
+    t0 [label="Decimal\ndecimal · Pool"];
+    t1 [label="FeeTier\nenum · Pool"];
+    t2 [label="(Decimal,)\ntuple · Pool"];
+    t3 [label="PoolState\nstruct · Pool", style=filled, fillcolor="#bfdbfe"];
+    t4 [label="SwapEvent\nstruct · Pool", style=filled, fillcolor="#fde68a"];
+    t1 -> t2 [label="Custom"];
+    t2 -> t0 [label="0"];
+    t3 -> t1 [label="fee_tier"];
+    t4 -> t0 [label="amount"];
+}
+"##
+        );
+    }
+}
@@ -0,0 +1,64 @@
+//! Turning names from SBOR schemas into identifiers of the generated
+//! languages. Type, field and variant names come straight from the package
+//! metadata, so they can contain anything. The languages only differ in the
+//! characters an identifier can contain and in their reserved words.
+
+/// Whether a character can be part of an identifier in every generated
+/// language.
+pub fn is_identifier_part(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+/// Turns a name into an identifier. Runs of characters that `is_part`
+/// rejects become a single `_`, so `HashMap<K, V>` becomes `HashMap_K_V`.
+/// Names that are empty or start with a digit get a leading `_`, and
+/// `keywords` get a trailing `_`.
+pub fn sanitize(
+    name: &str,
+    is_part: fn(char) -> bool,
+    keywords: &[&str],
+) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut pending_separator = false;
+    for c in name.chars() {
+        if is_part(c) {
+            if pending_separator
+                && !result.is_empty()
+                && !result.ends_with('_')
+                && c != '_'
+            {
+                result.push('_');
+            }
+            pending_separator = false;
+            result.push(c);
+        } else {
+            pending_separator = true;
+        }
+    }
+    if !result.starts_with(|c: char| is_part(c) && !c.is_ascii_digit()) {
+        result.insert(0, '_');
+    }
+    if keywords.contains(&result.as_str()) {
+        result.push('_');
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_are_sanitized() {
+        let keywords = &["type"];
+        let sanitize = |name| sanitize(name, is_identifier_part, keywords);
+        assert_eq!(sanitize("Pool"), "Pool");
+        assert_eq!(sanitize("HashMap<K, V>"), "HashMap_K_V");
+        assert_eq!(sanitize("Pool_State_0"), "Pool_State_0");
+        assert_eq!(sanitize("my-_field"), "my_field");
+        assert_eq!(sanitize("0th"), "_0th");
+        assert_eq!(sanitize("<>"), "_");
+        assert_eq!(sanitize("type"), "type_");
+        assert_eq!(sanitize("$type"), "type_");
+    }
+}
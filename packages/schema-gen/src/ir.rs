@@ -0,0 +1,247 @@
+//! A JSON form of the intermediate representation, for tools in other
+//! languages, and for running generation and rendering separately. The
+//! document holds the entries, the roots of every blueprint, the variable
+//! names and the labeled edges between entries. Enums are tagged with a
+//! `kind` field next to their fields in `value`.
+//! The document carries [`IR_VERSION`], which is bumped on every change that
+//! older readers can't ignore, and documents of other versions are rejected.
+
+use crate::ez_mode_gen::SchemaRegistry;
+use crate::renderer::{RenderOptions, Renderer};
+use std::error::Error;
+
+/// The version of the JSON IR.
+pub const IR_VERSION: u32 = 1;
+
+/// A reference from one entry to another, labeled by where it is used, see
+/// [`SborEzModeSchemaKind::children`]. The edges follow from the entries,
+/// and are only exported for convenience.
+#[derive(serde::Serialize)]
+struct Edge {
+    from: u32,
+    to: u32,
+    label: String,
+}
+
+#[derive(serde::Serialize)]
+struct IrExport<'a> {
+    version: u32,
+    package_address: &'a str,
+    #[serde(flatten)]
+    registry: &'a SchemaRegistry,
+    edges: Vec<Edge>,
+}
+
+#[derive(serde::Deserialize)]
+struct IrImport {
+    package_address: String,
+    #[serde(flatten)]
+    registry: SchemaRegistry,
+}
+
+/// Renders the whole registry as JSON IR. The filter is ignored, so that
+/// the IR can be rendered with any filter later on.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct IrJsonRenderer;
+
+impl Renderer for IrJsonRenderer {
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        _options: &RenderOptions,
+    ) -> String {
+        let edges = registry
+            .entries
+            .iter()
+            .enumerate()
+            .flat_map(|(from, entry)| {
+                entry.schema_kind.children().into_iter().map(
+                    move |(label, to)| Edge {
+                        from: from as u32,
+                        to,
+                        label,
+                    },
+                )
+            })
+            .collect();
+        let export = IrExport {
+            version: IR_VERSION,
+            package_address,
+            registry,
+            edges,
+        };
+        serde_json::to_string_pretty(&export).expect("Failed to serialize IR")
+    }
+}
+
+/// Reads a registry and its package address back from JSON IR.
+pub fn read_ir(json: &str) -> Result<(SchemaRegistry, String), Box<dyn Error>> {
+    let value: serde_json::Value = serde_json::from_str(json)?;
+    match value.get("version").and_then(|version| version.as_u64()) {
+        Some(version) if version == IR_VERSION as u64 => {}
+        Some(version) => {
+            return Err(format!(
+                "Unsupported IR version {}, expected {}",
+                version, IR_VERSION
+            )
+            .into())
+        }
+        None => return Err("The IR has no version".into()),
+    }
+    let IrImport {
+        package_address,
+        mut registry,
+    } = serde_json::from_value(value)?;
+
+    // The renderers index the entries directly, so references to missing
+    // entries are rejected here rather than panicking later.
+    let count = registry.entries.len() as u32;
+    let check = |index: u32| -> Result<(), Box<dyn Error>> {
+        if index < count {
+            Ok(())
+        } else {
+            Err(format!("The IR refers to missing entry {}", index).into())
+        }
+    };
+    for entry in &registry.entries {
+        for (_, child) in entry.schema_kind.children() {
+            check(child)?;
+        }
+        for &dependency in &entry.dependencies {
+            check(dependency)?;
+        }
+    }
+    for roots in &registry.roots {
+        let functions = roots
+            .functions
+            .iter()
+            .flat_map(|function| [function.input, function.output]);
+        for index in roots
+            .state
+            .into_iter()
+            .chain(roots.events.iter().map(|(_, index)| *index))
+            .chain(functions)
+        {
+            check(index)?;
+        }
+    }
+    if registry.names.len() != registry.entries.len() {
+        registry.assign_names(&Default::default());
+    }
+    Ok((registry, package_address))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{
+        testing::entry, BlueprintRoots, EnumVariant, ReferenceKind,
+        SborEzModeSchemaKind,
+    };
+    use crate::naming::RenameMap;
+    use crate::rust_gen::RustRenderer;
+    use crate::well_known::WellKnownType;
+
+    fn registry() -> SchemaRegistry {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::Address {
+                    reference: ReferenceKind::Resource,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::WellKnown(WellKnownType::Instant),
+            ),
+            entry(
+                Some("FeeTier"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![EnumVariant {
+                        discriminator: 0,
+                        name: "Low".to_string(),
+                        payload: None,
+                    }],
+                },
+            ),
+            entry(
+                Some("SwapEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("amount".to_string(), 0),
+                        ("resource".to_string(), 1),
+                        ("at".to_string(), 2),
+                        ("fee_tier".to_string(), 3),
+                    ],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: None,
+            events: vec![("SwapEvent".to_string(), 4)],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        registry
+    }
+
+    #[test]
+    fn test_ir_round_trips() {
+        let registry = registry();
+        let options = RenderOptions::default();
+        let json = IrJsonRenderer.render(&registry, "package_pool", &options);
+
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["version"], IR_VERSION);
+        assert_eq!(
+            value["entries"][1]["schema_kind"],
+            serde_json::json!({
+                "kind": "Address",
+                "value": { "reference": { "kind": "Resource" } },
+            })
+        );
+        assert_eq!(
+            value["entries"][4]["dependencies"],
+            serde_json::json!([0, 1, 2, 3])
+        );
+        assert_eq!(
+            value["edges"][0],
+            serde_json::json!({ "from": 4, "to": 0, "label": "amount" })
+        );
+
+        let (loaded, package_address) = read_ir(&json).unwrap();
+        assert_eq!(package_address, "package_pool");
+        assert_eq!(loaded.names, registry.names);
+        assert_eq!(
+            IrJsonRenderer.render(&loaded, "package_pool", &options),
+            json
+        );
+        assert_eq!(
+            RustRenderer::default().render(&loaded, "package_pool", &options),
+            RustRenderer::default().render(&registry, "package_pool", &options)
+        );
+    }
+
+    #[test]
+    fn test_other_versions_and_missing_entries_are_rejected() {
+        let json = IrJsonRenderer.render(
+            &registry(),
+            "package_pool",
+            &RenderOptions::default(),
+        );
+        let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+        value["version"] = (IR_VERSION + 1).into();
+        let error = read_ir(&value.to_string()).err().unwrap();
+        assert!(error.to_string().contains("Unsupported IR version"));
+
+        value["version"] = IR_VERSION.into();
+        value["roots"][0]["events"][0][1] = 9.into();
+        let error = read_ir(&value.to_string()).err().unwrap();
+        assert_eq!(error.to_string(), "The IR refers to missing entry 9");
+    }
+}
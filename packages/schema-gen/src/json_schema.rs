@@ -0,0 +1,663 @@
+//! Rendering of the registry as a JSON Schema (draft 2020-12).
+//! The schema describes values as they come out of the sbor-ez-mode parsers,
+//! not the programmatic JSON that goes into them: structs are objects, enums
+//! are `{ variant, value }` objects, and decimals are numeric strings.
+//! Non-inline types become `$defs`, which refer to each other with `$ref`.
+//!
+//! The parsers return maps as a JavaScript `Map`, which has no JSON form of
+//! its own. Maps with string keys are described as the object of
+//! `Object.fromEntries(map)`, other maps as the `[key, value]` entries of
+//! `Array.from(map)`.
+
+use crate::ez_mode_gen::{
+    is_inline, EnumVariant, RegistryEntry, SborEzModeSchemaKind,
+    SchemaRegistry, Selection, Validation, WideIntegerMode,
+};
+use crate::renderer::{RenderOptions, Renderer};
+use crate::well_known::WellKnownType;
+use serde::Serialize;
+use serde_json::{json, Map, Value};
+
+const DRAFT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+/// A bech32m encoded address, such as `account_rdx1...` or
+/// `internal_vault_tdx_2_1...`.
+const ADDRESS_PATTERN: &str = "^[a-z0-9_]+1[02-9ac-hj-np-z]{6,}$";
+
+/// A decimal number, which is parsed as a string to keep its precision.
+const DECIMAL_PATTERN: &str = "^-?[0-9]+(\\.[0-9]+)?$";
+
+/// An integer that is parsed as a string.
+const INTEGER_PATTERN: &str = "^-?[0-9]+$";
+
+/// Bytes, which are parsed as a hex string.
+const BYTES_PATTERN: &str = "^([0-9a-f]{2})*$";
+
+/// The string, integer, bytes and RUID forms of a non fungible local id.
+const LOCAL_ID_PATTERN: &str = "^(<[a-zA-Z0-9_]+>|#[0-9]+#|\\[([0-9a-f]{2})+\\]|\\{[0-9a-f]{16}(-[0-9a-f]{16}){3}\\})$";
+
+/// Renders the registry as a JSON Schema document.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonSchemaRenderer;
+
+impl JsonSchemaRenderer {
+    /// The schema of an entry, with its bounds applied.
+    pub fn entry_schema(
+        &self,
+        registry: &SchemaRegistry,
+        entry: &RegistryEntry,
+        options: &RenderOptions,
+    ) -> Value {
+        let mut schema = self.kind_schema(registry, entry, options);
+        if let (Some(validation), Value::Object(object)) =
+            (&entry.validation, &mut schema)
+        {
+            apply_validation(object, validation);
+        }
+        schema
+    }
+
+    fn kind_schema(
+        &self,
+        registry: &SchemaRegistry,
+        entry: &RegistryEntry,
+        options: &RenderOptions,
+    ) -> Value {
+        let child = |index: u32| self.reference(registry, index, options);
+        match &entry.schema_kind {
+            SborEzModeSchemaKind::Struct { fields } => {
+                let lenient = registry.is_lenient(entry, options);
+                let properties: Map<String, Value> = fields
+                    .iter()
+                    .map(|(name, field_type)| {
+                        let field = child(*field_type);
+                        let field = if lenient {
+                            json!({ "anyOf": [field, { "type": "null" }] })
+                        } else {
+                            field
+                        };
+                        (name.clone(), field)
+                    })
+                    .collect();
+                let required: Vec<&String> =
+                    fields.iter().map(|(name, _)| name).collect();
+                json!({
+                    "type": "object",
+                    "properties": properties,
+                    "required": required,
+                    "additionalProperties": false,
+                })
+            }
+            SborEzModeSchemaKind::Tuple { fields } => {
+                let items: Vec<Value> =
+                    fields.iter().map(|&field| child(field)).collect();
+                tuple(items)
+            }
+            SborEzModeSchemaKind::Array { element_type } => {
+                json!({ "type": "array", "items": child(*element_type) })
+            }
+            SborEzModeSchemaKind::Map {
+                key_type,
+                value_type,
+            } => {
+                // Only string keys survive `Object.fromEntries` as they are.
+                let key = &registry.entries[*key_type as usize];
+                let key_schema = self.entry_schema(registry, key, options);
+                if key_schema["type"] == "string" {
+                    json!({
+                        "type": "object",
+                        "propertyNames": child(*key_type),
+                        "additionalProperties": child(*value_type),
+                        "description": "A Map, as Object.fromEntries(map)",
+                    })
+                } else {
+                    json!({
+                        "type": "array",
+                        "items": tuple(vec![
+                            child(*key_type),
+                            child(*value_type),
+                        ]),
+                        "description": "A Map, as the [key, value] entries of Array.from(map)",
+                    })
+                }
+            }
+            SborEzModeSchemaKind::Enum { variants } => {
+                let mut schemas: Vec<Value> = variants
+                    .iter()
+                    .map(|variant| {
+                        variant_schema(
+                            &variant.name,
+                            variant.payload.map(child),
+                        )
+                    })
+                    .collect();
+                if registry.is_lenient(entry, options) {
+                    schemas.push(fallback_schema(variants));
+                }
+                json!({ "oneOf": schemas })
+            }
+            SborEzModeSchemaKind::Option { some } => json!({
+                "oneOf": [
+                    variant_schema("None", None),
+                    variant_schema("Some", Some(child(*some))),
+                ]
+            }),
+            SborEzModeSchemaKind::Result { ok, err } => json!({
+                "oneOf": [
+                    variant_schema("Ok", Some(tuple(vec![child(*ok)]))),
+                    variant_schema("Err", Some(tuple(vec![child(*err)]))),
+                ]
+            }),
+            SborEzModeSchemaKind::Decimal
+            | SborEzModeSchemaKind::PreciseDecimal => {
+                let precision = entry.schema_kind.decimal_precision().unwrap();
+                json!({
+                    "type": "string",
+                    "pattern": DECIMAL_PATTERN,
+                    "description": format!(
+                        "A {} with {} decimal places",
+                        precision.name, precision.decimal_places
+                    ),
+                })
+            }
+            SborEzModeSchemaKind::Address { reference } => {
+                address(reference.describe())
+            }
+            SborEzModeSchemaKind::InternalAddress { own } => {
+                address(own.describe())
+            }
+            SborEzModeSchemaKind::Integer { bits, signed } => {
+                integer(*bits, *signed, options.wide_integers)
+            }
+            SborEzModeSchemaKind::String => json!({ "type": "string" }),
+            SborEzModeSchemaKind::Bool => json!({ "type": "boolean" }),
+            SborEzModeSchemaKind::NonFungibleLocalId => {
+                json!({ "type": "string", "pattern": LOCAL_ID_PATTERN })
+            }
+            // Values that aren't parsed stay programmatic JSON.
+            SborEzModeSchemaKind::Value => json!({
+                "type": "object",
+                "required": ["kind"],
+                "description": "A programmatic SBOR JSON value",
+            }),
+            SborEzModeSchemaKind::Bytes => bytes(None),
+            SborEzModeSchemaKind::WellKnown(well_known) => {
+                well_known_schema(*well_known)
+            }
+        }
+    }
+
+    /// A reference to another entry, either inline or to its definition.
+    fn reference(
+        &self,
+        registry: &SchemaRegistry,
+        index: u32,
+        options: &RenderOptions,
+    ) -> Value {
+        let entry = &registry.entries[index as usize];
+        if is_inline(entry) {
+            self.entry_schema(registry, entry, options)
+        } else {
+            definition_ref(registry.var_name(index))
+        }
+    }
+}
+
+impl Renderer for JsonSchemaRenderer {
+    /// Render every reachable non-inline type, and every root, as a
+    /// definition. The document itself matches any of the roots.
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let Selection {
+            blueprints,
+            types,
+            reachable,
+        } = registry.select(&options.filter);
+        let roots: Vec<u32> = blueprints
+            .iter()
+            .flat_map(|roots| {
+                roots
+                    .state
+                    .into_iter()
+                    .chain(roots.events.iter().map(|(_, index)| *index))
+            })
+            .chain(types)
+            .collect();
+
+        let mut definitions = Map::new();
+        let mut indices: Vec<u32> = reachable.into_iter().collect();
+        indices.sort();
+        for i in indices {
+            let entry = &registry.entries[i as usize];
+            if is_inline(entry) && !roots.contains(&i) {
+                continue;
+            }
+            definitions.insert(
+                registry.var_name(i).to_string(),
+                self.entry_schema(registry, entry, options),
+            );
+        }
+
+        let mut root_refs: Vec<Value> = Vec::new();
+        for &root in &roots {
+            let root_ref = definition_ref(registry.var_name(root));
+            if !root_refs.contains(&root_ref) {
+                root_refs.push(root_ref);
+            }
+        }
+
+        let document = json!({
+            "$schema": DRAFT,
+            "title": format!("Types of package {}", package_address),
+            "description": "Values of the package types, as parsed by sbor-ez-mode",
+            "anyOf": root_refs,
+            "$defs": definitions,
+        });
+        let indent = " ".repeat(options.pretty.indent_width);
+        let formatter =
+            serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+        let mut output = Vec::new();
+        let mut serializer =
+            serde_json::Serializer::with_formatter(&mut output, formatter);
+        document.serialize(&mut serializer).unwrap();
+        output.push(b'\n');
+        String::from_utf8(output).unwrap()
+    }
+}
+
+fn definition_ref(name: &str) -> Value {
+    json!({ "$ref": format!("#/$defs/{}", name) })
+}
+
+/// An array with a fixed number of items of the given types.
+fn tuple(items: Vec<Value>) -> Value {
+    let len = items.len();
+    json!({
+        "type": "array",
+        "prefixItems": items,
+        "minItems": len,
+        "maxItems": len,
+    })
+}
+
+/// A parsed enum variant, which only has a `value` if it has fields.
+fn variant_schema(name: &str, value: Option<Value>) -> Value {
+    match value {
+        Some(value) => json!({
+            "type": "object",
+            "properties": { "variant": { "const": name }, "value": value },
+            "required": ["variant", "value"],
+            "additionalProperties": false,
+        }),
+        None => json!({
+            "type": "object",
+            "properties": { "variant": { "const": name } },
+            "required": ["variant"],
+            "additionalProperties": false,
+        }),
+    }
+}
+
+/// The variants of a lenient enum that aren't known, whose fields are kept
+/// as programmatic JSON.
+fn fallback_schema(variants: &[EnumVariant]) -> Value {
+    let known: Vec<&String> =
+        variants.iter().map(|variant| &variant.name).collect();
+    json!({
+        "type": "object",
+        "properties": {
+            "variant": { "type": "string", "not": { "enum": known } },
+            "value": { "type": "object", "required": ["kind"] },
+        },
+        "required": ["variant", "value"],
+        "additionalProperties": false,
+    })
+}
+
+fn address(description: Option<String>) -> Value {
+    let mut schema = json!({ "type": "string", "pattern": ADDRESS_PATTERN });
+    if let Some(description) = description {
+        schema["description"] = Value::String(description);
+    }
+    schema
+}
+
+fn bytes(description: Option<&str>) -> Value {
+    let mut schema = json!({ "type": "string", "pattern": BYTES_PATTERN });
+    if let Some(description) = description {
+        schema["description"] = Value::String(description.to_string());
+    }
+    schema
+}
+
+/// An integer, bounded by its width when it fits into a JSON number.
+fn integer(bits: u8, signed: bool, wide_integers: WideIntegerMode) -> Value {
+    if bits > 32 {
+        return match wide_integers {
+            WideIntegerMode::String => {
+                json!({ "type": "string", "pattern": INTEGER_PATTERN })
+            }
+            WideIntegerMode::Number | WideIntegerMode::BigInt => {
+                json!({ "type": "integer" })
+            }
+        };
+    }
+    let (min, max) = if signed {
+        (-(1i64 << (bits - 1)), (1i64 << (bits - 1)) - 1)
+    } else {
+        (0, (1i64 << bits) - 1)
+    };
+    json!({ "type": "integer", "minimum": min, "maximum": max })
+}
+
+fn well_known_schema(well_known: WellKnownType) -> Value {
+    match well_known {
+        // Instants are parsed into a `Date`, which serializes as a string.
+        WellKnownType::Instant => {
+            json!({ "type": "string", "format": "date-time" })
+        }
+        WellKnownType::UtcDateTime => {
+            let fields =
+                ["year", "month", "day_of_month", "hour", "minute", "second"];
+            let properties: Map<String, Value> = fields
+                .iter()
+                .map(|field| (field.to_string(), json!({ "type": "integer" })))
+                .collect();
+            json!({
+                "type": "object",
+                "properties": properties,
+                "required": fields,
+                "additionalProperties": false,
+            })
+        }
+        WellKnownType::NonFungibleGlobalId => tuple(vec![
+            address(Some("ResourceAddress".to_string())),
+            json!({ "type": "string", "pattern": LOCAL_ID_PATTERN }),
+        ]),
+        WellKnownType::PublicKey | WellKnownType::PublicKeyHash => json!({
+            "oneOf": [
+                variant_schema("Secp256k1", Some(tuple(vec![bytes(None)]))),
+                variant_schema("Ed25519", Some(tuple(vec![bytes(None)]))),
+            ],
+            "description": well_known.name(),
+        }),
+        WellKnownType::Hash
+        | WellKnownType::Secp256k1PublicKey
+        | WellKnownType::Ed25519PublicKey
+        | WellKnownType::Secp256k1PublicKeyHash
+        | WellKnownType::Ed25519PublicKeyHash => bytes(Some(well_known.name())),
+    }
+}
+
+/// Adds the bounds of a type to its schema, using the keywords that match
+/// the type of the schema.
+fn apply_validation(schema: &mut Map<String, Value>, validation: &Validation) {
+    let schema_type = schema
+        .get("type")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    match (validation, schema_type.as_deref()) {
+        (Validation::Length { min, max }, Some(schema_type)) => {
+            let (min_keyword, max_keyword) = match schema_type {
+                "string" => ("minLength", "maxLength"),
+                _ => ("minItems", "maxItems"),
+            };
+            if let Some(min) = min {
+                schema.insert(min_keyword.to_string(), json!(min));
+            }
+            if let Some(max) = max {
+                schema.insert(max_keyword.to_string(), json!(max));
+            }
+        }
+        (Validation::Numeric { min, max }, Some("integer")) => {
+            // Bounds that don't fit into a JSON number are left out.
+            let number = |bound: &Option<String>| {
+                bound.as_deref().and_then(|bound| {
+                    serde_json::from_str::<serde_json::Number>(bound)
+                        .ok()
+                        .filter(|number| number.is_i64() || number.is_u64())
+                })
+            };
+            if let Some(min) = number(min) {
+                schema.insert("minimum".to_string(), Value::Number(min));
+            }
+            if let Some(max) = number(max) {
+                schema.insert("maximum".to_string(), Value::Number(max));
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{testing::entry, BlueprintRoots, ReferenceKind};
+    use crate::naming::RenameMap;
+
+    /// Whether a value matches a schema of the document. This covers the
+    /// keywords that shape the values, not `pattern` or the bounds.
+    fn matches(document: &Value, schema: &Value, value: &Value) -> bool {
+        if let Some(reference) = schema["$ref"].as_str() {
+            let name = reference.trim_start_matches("#/$defs/");
+            return matches(document, &document["$defs"][name], value);
+        }
+        if let Some(schemas) = schema["oneOf"].as_array() {
+            let matching = schemas
+                .iter()
+                .filter(|schema| matches(document, schema, value))
+                .count();
+            return matching == 1;
+        }
+        if let Some(schemas) = schema["anyOf"].as_array() {
+            return schemas
+                .iter()
+                .any(|schema| matches(document, schema, value));
+        }
+        if let Some(constant) = schema.get("const") {
+            return constant == value;
+        }
+        match (schema["type"].as_str(), value) {
+            (Some("string"), Value::String(_))
+            | (Some("boolean"), Value::Bool(_))
+            | (Some("null"), Value::Null) => true,
+            (Some("integer"), Value::Number(number)) => {
+                number.is_i64() || number.is_u64()
+            }
+            (Some("object"), Value::Object(object)) => {
+                let required =
+                    schema["required"].as_array().into_iter().flatten().all(
+                        |name| object.contains_key(name.as_str().unwrap()),
+                    );
+                required
+                    && object.iter().all(|(key, field)| {
+                        let key_matches =
+                            schema.get("propertyNames").is_none_or(|names| {
+                                matches(document, names, &json!(key))
+                            });
+                        let field_matches = match (
+                            schema["properties"].get(key),
+                            schema.get("additionalProperties"),
+                        ) {
+                            (Some(property), _) => {
+                                matches(document, property, field)
+                            }
+                            (None, Some(Value::Bool(allowed))) => *allowed,
+                            (None, Some(additional)) => {
+                                matches(document, additional, field)
+                            }
+                            (None, None) => true,
+                        };
+                        key_matches && field_matches
+                    })
+            }
+            (Some("array"), Value::Array(items)) => {
+                match schema["prefixItems"].as_array() {
+                    Some(prefix) => {
+                        prefix.len() == items.len()
+                            && prefix.iter().zip(items).all(|(schema, item)| {
+                                matches(document, schema, item)
+                            })
+                    }
+                    None => items
+                        .iter()
+                        .all(|item| matches(document, &schema["items"], item)),
+                }
+            }
+            _ => false,
+        }
+    }
+
+    #[test]
+    fn test_types_are_described_as_parsed() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::Address {
+                    reference: ReferenceKind::Resource,
+                },
+            ),
+            entry(
+                Some("Deposit"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("amount".to_string(), 0),
+                        ("resource".to_string(), 1),
+                    ],
+                },
+            ),
+            entry(
+                Some("Action"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![
+                        EnumVariant {
+                            discriminator: 0,
+                            name: "Idle".to_string(),
+                            payload: None,
+                        },
+                        EnumVariant {
+                            discriminator: 1,
+                            name: "Deposit".to_string(),
+                            payload: Some(2),
+                        },
+                    ],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Vault".to_string(),
+            state: Some(3),
+            events: Vec::new(),
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output = JsonSchemaRenderer.render(
+            &registry,
+            "package_json",
+            &RenderOptions::default(),
+        );
+        let document: Value = serde_json::from_str(&output).unwrap();
+
+        assert_eq!(document["$schema"], DRAFT);
+        assert_eq!(document["anyOf"], json!([{ "$ref": "#/$defs/Action" }]));
+        let definitions = document["$defs"].as_object().unwrap();
+        assert_eq!(
+            definitions.keys().collect::<Vec<_>>(),
+            vec!["Action", "Deposit"]
+        );
+        assert_eq!(
+            definitions["Deposit"]["properties"]["amount"]["pattern"],
+            DECIMAL_PATTERN
+        );
+        assert_eq!(
+            definitions["Deposit"]["properties"]["resource"],
+            json!({
+                "type": "string",
+                "pattern": ADDRESS_PATTERN,
+                "description": "ResourceAddress",
+            })
+        );
+        assert_eq!(
+            definitions["Action"]["oneOf"][0],
+            variant_schema("Idle", None)
+        );
+        assert_eq!(
+            definitions["Action"]["oneOf"][1]["properties"]["value"],
+            json!({ "$ref": "#/$defs/Deposit" })
+        );
+    }
+
+    #[test]
+    fn test_parsed_maps_match_the_schema() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::String),
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 32,
+                    signed: false,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Map {
+                    key_type: 0,
+                    value_type: 1,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Map {
+                    key_type: 2,
+                    value_type: 0,
+                },
+            ),
+            entry(
+                Some("PoolState"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("balances".to_string(), 3),
+                        ("names".to_string(), 4),
+                    ],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(5),
+            events: Vec::new(),
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output = JsonSchemaRenderer.render(
+            &registry,
+            "package_maps",
+            &RenderOptions::default(),
+        );
+        let document: Value = serde_json::from_str(&output).unwrap();
+
+        // The parsed maps, as `Object.fromEntries` and `Array.from` convert
+        // them.
+        let parsed = json!({
+            "balances": { "xrd": "1.5", "usd": "20" },
+            "names": [[1, "one"], [2, "two"]],
+        });
+        assert!(matches(&document, &document, &parsed));
+        // A `Map` that isn't converted serializes as an empty object.
+        let unconverted = json!({ "balances": {}, "names": {} });
+        assert!(!matches(&document, &document, &unconverted));
+        let entries = json!({
+            "balances": [["xrd", "1.5"]],
+            "names": [[1, "one"]],
+        });
+        assert!(!matches(&document, &document, &entries));
+    }
+}
@@ -1,2 +1,18 @@
+pub mod doc;
+pub mod dts;
+pub mod extern_blueprint;
 pub mod ez_mode_gen;
 pub mod gateway;
+pub mod graph;
+pub mod identifier;
+pub mod ir;
+pub mod json_schema;
+pub mod naming;
+pub mod package_definition;
+pub mod pretty;
+pub mod python_gen;
+pub mod renderer;
+pub mod rust_gen;
+pub mod sql;
+pub mod ts_escape;
+pub mod well_known;
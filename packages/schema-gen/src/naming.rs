@@ -0,0 +1,607 @@
+//! This module decides which variable name each registry entry gets in the
+//! generated output.
+//! Names only depend on the blueprint a type was found in and on where it is
+//! used, never on its position in the registry. That way adding an unrelated
+//! type to a package doesn't rename everything that was generated before.
+//!
+//! - Named types keep their name if it is unique in the package, and are
+//!   qualified with their blueprint on a collision (`Pool_SwapEvent`).
+//! - Anonymous types are named after the path they were first reached by,
+//!   starting from a named type (`Pool_State_fees_Item`).
+//! - Blueprint names are reserved for the per-blueprint export maps. A state
+//!   type named after its blueprint becomes `PoolState`.
+//! - Names of well known type helpers such as `NonFungibleGlobalId`, and of
+//!   the TypeScript globals the output refers to such as `Date`, are
+//!   reserved as well.
+//! - Types that are only used by function signatures never change the names
+//!   of state and event types, because most formats don't render functions.
+//!   They don't count towards collisions of those types, and never name the
+//!   anonymous types they share with them.
+//! - Names are turned into valid identifiers (`HashMap<K, V>` becomes
+//!   `HashMap_K_V`), see [`crate::ts_escape`], and names that are still
+//!   taken get a numbered suffix.
+//! - A user provided rename map is applied last, to the names as they
+//!   appear in the output, so any generated name can be overridden. Renames
+//!   to a name that is already taken are left out, see [`rename_warnings`].
+
+use crate::ez_mode_gen::{is_inline, SchemaRegistry};
+use crate::ts_escape::identifier;
+use std::collections::{HashMap, HashSet, VecDeque};
+
+/// Maps generated names onto the names that should be used instead.
+pub type RenameMap = HashMap<String, String>;
+
+/// Computes a variable name for every entry in the registry, indexed the
+/// same way as the registry entries.
+pub fn assign_names(
+    registry: &SchemaRegistry,
+    renames: &RenameMap,
+) -> Vec<String> {
+    let entries = &registry.entries;
+    let mut names: Vec<Option<String>> = vec![None; entries.len()];
+    // The prefix used for types that are reached from this entry.
+    let mut contexts: Vec<Option<String>> = vec![None; entries.len()];
+
+    let reserved = reserved_names(registry);
+    let state_roots: HashSet<usize> = registry
+        .roots
+        .iter()
+        .filter_map(|roots| roots.state.map(|state| state as usize))
+        .collect();
+
+    let function_only = function_only(registry);
+    // Occurrences among the state and event types, and among all types.
+    let mut occurrences: HashMap<&str, usize> = HashMap::new();
+    let mut all_occurrences: HashMap<&str, usize> = HashMap::new();
+    for (i, entry) in entries.iter().enumerate() {
+        if let Some(name) = &entry.type_name {
+            if !function_only.contains(&i) {
+                *occurrences.entry(name.as_str()).or_default() += 1;
+            }
+            *all_occurrences.entry(name.as_str()).or_default() += 1;
+        }
+    }
+
+    for (i, entry) in entries.iter().enumerate() {
+        let Some(name) = &entry.type_name else {
+            continue;
+        };
+        let qualified = qualify(entry.blueprint.as_deref(), name);
+        names[i] = Some(if reserved.contains(&identifier(name)) {
+            if state_roots.contains(&i) {
+                format!("{}State", name)
+            } else {
+                qualified.clone()
+            }
+        } else if function_only.contains(&i) {
+            if all_occurrences[name.as_str()] > 1 {
+                qualified.clone()
+            } else {
+                name.clone()
+            }
+        } else if occurrences[name.as_str()] > 1 {
+            qualified.clone()
+        } else {
+            name.clone()
+        });
+        // Named wrappers such as `Option` are rendered inline, so types
+        // reached through them are named after the path instead.
+        if !is_inline(entry) {
+            contexts[i] = Some(qualified);
+        }
+    }
+
+    // Walk from the named types in a stable order, and name every anonymous
+    // type after the first path it is found by. The walk from the state and
+    // event types is finished before the function signatures are walked.
+    let mut named: Vec<usize> = (0..entries.len())
+        .filter(|&i| contexts[i].is_some())
+        .collect();
+    named.sort_by(|&a, &b| {
+        contexts[a]
+            .cmp(&contexts[b])
+            .then_with(|| entries[a].type_hash.0.cmp(&entries[b].type_hash.0))
+    });
+    let (functions, named): (Vec<usize>, Vec<usize>) =
+        named.into_iter().partition(|i| function_only.contains(i));
+    for seeds in [named, functions] {
+        let mut queue: VecDeque<usize> = seeds.into_iter().collect();
+        while let Some(i) = queue.pop_front() {
+            let context = contexts[i].clone().unwrap();
+            for (label, child) in entries[i].schema_kind.children() {
+                let child = child as usize;
+                if contexts[child].is_some() {
+                    continue;
+                }
+                let name = format!("{}_{}", context, label);
+                if names[child].is_none() {
+                    names[child] = Some(name.clone());
+                }
+                contexts[child] = Some(name);
+                queue.push_back(child);
+            }
+        }
+    }
+
+    let names: Vec<String> = names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let name = name.unwrap_or_else(|| {
+                qualify(entries[i].blueprint.as_deref(), "Anonymous")
+            });
+            identifier(&name)
+        })
+        .collect();
+
+    let mut names = deduplicate(registry, names, &function_only);
+    apply_renames(registry, &mut names, renames);
+    names
+}
+
+/// Warnings about the renames that [`assign_names`] leaves out: names that
+/// aren't generated, and renames to a name that is already taken.
+pub fn rename_warnings(
+    registry: &SchemaRegistry,
+    renames: &RenameMap,
+) -> Vec<String> {
+    let mut names = assign_names(registry, &RenameMap::new());
+    apply_renames(registry, &mut names, renames)
+}
+
+/// Renames the final names, in the order of the names that are renamed so
+/// that the result doesn't depend on the order of the map. Returns warnings
+/// about the renames that are left out.
+fn apply_renames(
+    registry: &SchemaRegistry,
+    names: &mut [String],
+    renames: &RenameMap,
+) -> Vec<String> {
+    let mut taken = reserved_names(registry);
+    taken.extend(names.iter().cloned());
+    let mut renames: Vec<(&String, &String)> = renames.iter().collect();
+    renames.sort();
+    let mut warnings = Vec::new();
+    for (from, to) in renames {
+        let Some(i) = names.iter().position(|name| name == from) else {
+            warnings.push(format!(
+                "{} is not a generated name, so it isn't renamed",
+                from
+            ));
+            continue;
+        };
+        let to = identifier(to);
+        if to == *from {
+            continue;
+        }
+        if taken.contains(&to) {
+            warnings.push(format!(
+                "{} isn't renamed to {}, which is already taken",
+                from, to
+            ));
+            continue;
+        }
+        taken.remove(from);
+        taken.insert(to.clone());
+        names[i] = to;
+    }
+    warnings
+}
+
+/// The entries that are only reachable from function signatures, and not
+/// from the state or events of any blueprint.
+fn function_only(registry: &SchemaRegistry) -> HashSet<usize> {
+    let data = registry.reachable(registry.roots.iter().flat_map(|roots| {
+        roots
+            .state
+            .into_iter()
+            .chain(roots.events.iter().map(|(_, index)| *index))
+    }));
+    registry
+        .reachable(registry.roots.iter().flat_map(|roots| {
+            roots
+                .functions
+                .iter()
+                .flat_map(|function| [function.input, function.output])
+        }))
+        .into_iter()
+        .filter(|index| !data.contains(index))
+        .map(|index| index as usize)
+        .collect()
+}
+
+/// Variables declared by the generated code itself, and the TypeScript
+/// globals it refers to, which a declaration of the same name would shadow.
+const GENERATED_NAMES: &[&str] = &[
+    "s",
+    "eventSchemas",
+    "eventTable",
+    "decodeEvent",
+    "Array",
+    "Date",
+    "Map",
+];
+
+/// Names that are taken by the per-blueprint export maps, by the helpers of
+/// well known types and by the generated code itself.
+fn reserved_names(registry: &SchemaRegistry) -> HashSet<String> {
+    registry
+        .roots
+        .iter()
+        .map(|roots| identifier(&roots.blueprint))
+        .chain(GENERATED_NAMES.iter().map(|name| name.to_string()))
+        .chain(
+            registry
+                .well_known_helpers()
+                .into_iter()
+                .map(|(name, _)| name.to_string()),
+        )
+        .collect()
+}
+
+fn qualify(blueprint: Option<&str>, name: &str) -> String {
+    match blueprint {
+        Some(blueprint) => format!("{}_{}", blueprint, name),
+        None => name.to_string(),
+    }
+}
+
+/// As a last resort, give names that are still taken a numbered suffix.
+/// This only happens when a blueprint has several different types with the
+/// same name, for example generic instantiations. State and event types get
+/// first pick, then types that are rendered separately, so neither function
+/// signatures nor inline types push them aside.
+fn deduplicate(
+    registry: &SchemaRegistry,
+    names: Vec<String>,
+    function_only: &HashSet<usize>,
+) -> Vec<String> {
+    let entries = &registry.entries;
+    let mut order: Vec<usize> = (0..names.len()).collect();
+    order.sort_by(|&a, &b| {
+        function_only
+            .contains(&a)
+            .cmp(&function_only.contains(&b))
+            .then_with(|| is_inline(&entries[a]).cmp(&is_inline(&entries[b])))
+            .then_with(|| names[a].cmp(&names[b]))
+            .then_with(|| entries[a].type_hash.0.cmp(&entries[b].type_hash.0))
+    });
+
+    let mut taken = reserved_names(registry);
+    let mut result = names.clone();
+    for i in order {
+        let mut name = names[i].clone();
+        let mut suffix = 2;
+        while taken.contains(&name) {
+            name = format!("{}_{}", names[i], suffix);
+            suffix += 1;
+        }
+        taken.insert(name.clone());
+        result[i] = name;
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{
+        BlueprintRoots, EnumVariant, FunctionRoots, RegistryEntry,
+        SborEzModeSchemaKind, TypeHash,
+    };
+    use crate::gateway::{Receiver, Visibility};
+    use std::collections::HashSet;
+
+    fn entry(
+        hash: &str,
+        name: Option<&str>,
+        blueprint: &str,
+        schema_kind: SborEzModeSchemaKind,
+    ) -> RegistryEntry {
+        RegistryEntry {
+            type_hash: TypeHash(hash.to_string()),
+            type_name: name.map(|s| s.to_string()),
+            schema_kind,
+            dependencies: HashSet::new(),
+            blueprint: Some(blueprint.to_string()),
+            validation: None,
+        }
+    }
+
+    fn registry(entries: Vec<RegistryEntry>) -> SchemaRegistry {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = entries;
+        registry
+    }
+
+    #[test]
+    fn test_collisions_are_qualified_by_blueprint_and_context() {
+        let entries = vec![
+            entry("decimal", None, "Pool", SborEzModeSchemaKind::Decimal),
+            entry(
+                "fee",
+                None,
+                "Pool",
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("amount".to_string(), 0)],
+                },
+            ),
+            entry(
+                "fee_variant",
+                None,
+                "Pool",
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![EnumVariant {
+                        discriminator: 0,
+                        name: "Flat".to_string(),
+                        payload: Some(1),
+                    }],
+                },
+            ),
+            entry(
+                "fees",
+                None,
+                "Pool",
+                SborEzModeSchemaKind::Array { element_type: 2 },
+            ),
+            entry(
+                "state",
+                Some("State"),
+                "Pool",
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("fees".to_string(), 3)],
+                },
+            ),
+            entry(
+                "pool_swap",
+                Some("SwapEvent"),
+                "Pool",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+            entry(
+                "router_swap",
+                Some("SwapEvent"),
+                "Router",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+        ];
+        let names = assign_names(&registry(entries.clone()), &RenameMap::new());
+        assert_eq!(names[2], "Pool_State_fees_Item");
+        assert_eq!(names[1], "Pool_State_fees_Item_Flat");
+        assert_eq!(names[4], "State");
+        assert_eq!(names[5], "Pool_SwapEvent");
+        assert_eq!(names[6], "Router_SwapEvent");
+
+        // Registering an unrelated type first doesn't change any names.
+        let mut shifted =
+            vec![entry("other", None, "Other", SborEzModeSchemaKind::String)];
+        shifted.extend(entries);
+        let shifted_names = assign_names(&registry(shifted), &RenameMap::new());
+        assert_eq!(&shifted_names[1..], &names[..]);
+    }
+
+    #[test]
+    fn test_function_signatures_dont_rename_state_and_events() {
+        let entries = vec![
+            entry("decimal", None, "Pool", SborEzModeSchemaKind::Decimal),
+            entry(
+                "fee",
+                None,
+                "Pool",
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("amount".to_string(), 0)],
+                },
+            ),
+            entry(
+                "state",
+                Some("State"),
+                "Pool",
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("fee".to_string(), 1)],
+                },
+            ),
+            entry(
+                "pool_swap",
+                Some("SwapEvent"),
+                "Pool",
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("amount".to_string(), 0)],
+                },
+            ),
+        ];
+        let roots = BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(2),
+            events: vec![("SwapEvent".to_string(), 3)],
+            functions: Vec::new(),
+        };
+        let mut before = registry(entries.clone());
+        before.roots.push(roots.clone());
+        let names = assign_names(&before, &RenameMap::new());
+        assert_eq!(names[1], "Pool_State_fee");
+        assert_eq!(names[2], "State");
+        assert_eq!(names[3], "SwapEvent");
+
+        // A function taking types that share names and anonymous types with
+        // the state and events.
+        let mut with_functions = entries;
+        with_functions.extend([
+            entry(
+                "router_swap",
+                Some("SwapEvent"),
+                "Router",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+            entry(
+                "swap_input",
+                Some("Pool_swap_Input"),
+                "Pool",
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("fee".to_string(), 1),
+                        ("event".to_string(), 4),
+                    ],
+                },
+            ),
+        ]);
+        let mut after = registry(with_functions);
+        after.roots.push(BlueprintRoots {
+            functions: vec![FunctionRoots {
+                name: "swap".to_string(),
+                receiver: Some(Receiver::SelfRefMut),
+                visibility: Visibility::Public,
+                input: 5,
+                output: 0,
+            }],
+            ..roots
+        });
+        let after_names = assign_names(&after, &RenameMap::new());
+        assert_eq!(&after_names[..4], &names[..]);
+        assert_eq!(after_names[4], "Router_SwapEvent");
+    }
+
+    #[test]
+    fn test_rename_map_overrides_generated_names() {
+        let entries = vec![
+            entry("decimal", None, "Pool", SborEzModeSchemaKind::Decimal),
+            entry(
+                "fee",
+                None,
+                "Pool",
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![EnumVariant {
+                        discriminator: 0,
+                        name: "Flat".to_string(),
+                        payload: Some(0),
+                    }],
+                },
+            ),
+            entry(
+                "state",
+                Some("State"),
+                "Pool",
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("fee".to_string(), 1)],
+                },
+            ),
+        ];
+        let renames = RenameMap::from([(
+            "Pool_State_fee".to_string(),
+            "FeeConfig".to_string(),
+        )]);
+        let names = assign_names(&registry(entries), &renames);
+        assert_eq!(names[1], "FeeConfig");
+    }
+
+    #[test]
+    fn test_renames_apply_to_the_names_in_the_output() {
+        let entries = vec![
+            entry(
+                "a",
+                Some("HashMap<K, V>"),
+                "Pool",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+            entry(
+                "b",
+                Some("Foo"),
+                "Pool",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+            entry(
+                "c",
+                Some("Foo"),
+                "Pool",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+        ];
+        let registry = registry(entries);
+        assert_eq!(
+            assign_names(&registry, &RenameMap::new()),
+            ["HashMap_K_V", "Pool_Foo", "Pool_Foo_2"]
+        );
+
+        let renames = RenameMap::from([
+            ("HashMap_K_V".to_string(), "Balances".to_string()),
+            ("Pool_Foo_2".to_string(), "Bar".to_string()),
+        ]);
+        assert_eq!(
+            assign_names(&registry, &renames),
+            ["Balances", "Pool_Foo", "Bar"]
+        );
+        assert!(rename_warnings(&registry, &renames).is_empty());
+    }
+
+    #[test]
+    fn test_colliding_renames_are_left_out() {
+        let entries = vec![
+            entry(
+                "a",
+                Some("Foo"),
+                "Pool",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+            entry(
+                "b",
+                Some("Bar"),
+                "Pool",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+        ];
+        let registry = registry(entries);
+        let renames = RenameMap::from([
+            ("Foo".to_string(), "Bar".to_string()),
+            ("Bar".to_string(), "Date".to_string()),
+            ("HashMap<K, V>".to_string(), "Balances".to_string()),
+        ]);
+        assert_eq!(assign_names(&registry, &renames), ["Foo", "Bar"]);
+        assert_eq!(
+            rename_warnings(&registry, &renames),
+            [
+                "Bar isn't renamed to Date, which is already taken",
+                "Foo isn't renamed to Bar, which is already taken",
+                "HashMap<K, V> is not a generated name, so it isn't renamed",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typescript_globals_are_reserved() {
+        let entries = vec![entry(
+            "date",
+            Some("Date"),
+            "Pool",
+            SborEzModeSchemaKind::Struct { fields: vec![] },
+        )];
+        let names = assign_names(&registry(entries), &RenameMap::new());
+        assert_eq!(names[0], "Pool_Date");
+    }
+
+    #[test]
+    fn test_blueprint_names_are_reserved_for_export_maps() {
+        let entries = vec![
+            entry(
+                "state",
+                Some("Pool"),
+                "Pool",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+            entry(
+                "other",
+                Some("Pool"),
+                "Router",
+                SborEzModeSchemaKind::Struct { fields: vec![] },
+            ),
+        ];
+        let mut registry = registry(entries);
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(0),
+            events: vec![],
+            functions: Vec::new(),
+        });
+        let names = assign_names(&registry, &RenameMap::new());
+        assert_eq!(names[0], "PoolState");
+        assert_eq!(names[1], "Router_Pool");
+    }
+}
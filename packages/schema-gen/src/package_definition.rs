@@ -0,0 +1,150 @@
+//! Reading blueprints from a package definition (`.rpd`) file, as written by
+//! `scrypto build`. This is an offline alternative to the gateway, which also
+//! works for packages that aren't deployed yet.
+
+use crate::gateway::{
+    BlueprintDefinition, BlueprintWithSchema, Function, Receiver, Schema, Type,
+    Visibility,
+};
+use sbor::{LocalTypeId, Versioned};
+use scrypto::prelude::{
+    hash, manifest_decode, scrypto_encode, AccessRule, BlueprintDefinitionInit,
+    FunctionAuth, MethodAccessibility, MethodAuthTemplate, MethodKey,
+    PackageDefinition, TypeRef,
+};
+use std::error::Error;
+
+/// Reads the blueprints of a package definition, in the same form as
+/// [`crate::gateway::get_blueprints_and_corresponding_schemas`].
+pub fn read_package_definition(
+    path: &str,
+) -> Result<Vec<BlueprintWithSchema>, Box<dyn Error>> {
+    let bytes = std::fs::read(path)?;
+    let definition: PackageDefinition = manifest_decode(&bytes)
+        .map_err(|e| format!("Could not decode package definition: {:?}", e))?;
+    definition
+        .blueprints
+        .into_iter()
+        .map(|(name, blueprint)| blueprint_with_schema(name, blueprint))
+        .collect()
+}
+
+fn blueprint_with_schema(
+    name: String,
+    blueprint: BlueprintDefinitionInit,
+) -> Result<BlueprintWithSchema, Box<dyn Error>> {
+    let schema = blueprint
+        .schema
+        .schema
+        .fully_update_and_into_latest_version();
+    let schema = scrypto_encode(&schema)
+        .map_err(|e| format!("Could not encode schema: {:?}", e))?;
+    let schema_hash = hash(&schema).to_string();
+
+    let static_type = |type_ref: &TypeRef<LocalTypeId>| match type_ref {
+        TypeRef::Static(type_id) => Ok(*type_id),
+        TypeRef::Generic(_) => Err(format!(
+            "Generic types of blueprint {} are not supported",
+            name
+        )),
+    };
+    let root = |root_name: &str, type_ref: &TypeRef<LocalTypeId>| {
+        let LocalTypeId::SchemaLocalIndex(index) = static_type(type_ref)?
+        else {
+            return Err(format!(
+                "The {} type of blueprint {} is a well known type",
+                root_name, name
+            ));
+        };
+        Ok(Type {
+            name: root_name.to_string(),
+            type_id: index as u32,
+            schema_hash: schema_hash.clone(),
+        })
+    };
+
+    let state = match blueprint.schema.state.fields.first() {
+        Some(field) => root(&name, &field.field)?,
+        None => return Err("No fields found in state".into()),
+    };
+    let mut events = blueprint
+        .schema
+        .events
+        .event_schema
+        .iter()
+        .map(|(event_name, type_ref)| root(event_name, type_ref))
+        .collect::<Result<Vec<_>, _>>()?;
+    events.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let auth = &blueprint.auth_config;
+    let mut functions = blueprint
+        .schema
+        .functions
+        .functions
+        .iter()
+        .map(|(function_name, function)| {
+            let receiver = function.receiver.as_ref().map(|receiver| {
+                match receiver.receiver {
+                    scrypto::prelude::Receiver::SelfRef => Receiver::SelfRef,
+                    scrypto::prelude::Receiver::SelfRefMut => {
+                        Receiver::SelfRefMut
+                    }
+                }
+            });
+            let visibility = match receiver {
+                Some(_) => method_visibility(&auth.method_auth, function_name),
+                None => function_visibility(&auth.function_auth, function_name),
+            };
+            Ok(Function {
+                name: function_name.clone(),
+                receiver,
+                visibility,
+                input: static_type(&function.input)?,
+                output: static_type(&function.output)?,
+            })
+        })
+        .collect::<Result<Vec<_>, String>>()?;
+    functions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(BlueprintWithSchema {
+        blueprint: BlueprintDefinition {
+            name,
+            state,
+            events,
+            functions,
+            schema_hash: schema_hash.clone(),
+        },
+        schema: Schema {
+            schema,
+            schema_hash,
+        },
+    })
+}
+
+fn function_visibility(auth: &FunctionAuth, name: &str) -> Visibility {
+    match auth {
+        FunctionAuth::AllowAll => Visibility::Public,
+        FunctionAuth::RootOnly => Visibility::Private,
+        FunctionAuth::AccessRules(rules) => match rules.get(name) {
+            None | Some(AccessRule::AllowAll) => Visibility::Public,
+            Some(AccessRule::DenyAll) => Visibility::Private,
+            Some(_) => Visibility::AccessRule,
+        },
+    }
+}
+
+fn method_visibility(auth: &MethodAuthTemplate, name: &str) -> Visibility {
+    let MethodAuthTemplate::StaticRoleDefinition(definition) = auth else {
+        return Visibility::Public;
+    };
+    match definition.methods.get(&MethodKey::new(name)) {
+        None | Some(MethodAccessibility::Public) => Visibility::Public,
+        Some(MethodAccessibility::RoleProtected(roles)) => Visibility::Roles(
+            roles.list.iter().map(|role| role.key.clone()).collect(),
+        ),
+        Some(
+            MethodAccessibility::OuterObjectOnly
+            | MethodAccessibility::OwnPackageOnly,
+        ) => Visibility::Private,
+    }
+}
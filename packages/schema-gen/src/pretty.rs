@@ -0,0 +1,324 @@
+//! A small pretty printer for the generated code.
+//! Renderers describe their output as a [`Doc`], in which groups are printed
+//! on a single line when they fit, and are broken over several indented
+//! lines otherwise. This is the algorithm from Wadler's "A prettier
+//! printer", which is also what prettier itself uses.
+
+/// A document that can be printed at different widths.
+#[derive(Clone, Debug)]
+pub enum Doc {
+    /// Text without newlines.
+    Text(String),
+    /// A space, or a newline if the enclosing group is broken.
+    Line,
+    /// Nothing, or a newline if the enclosing group is broken.
+    SoftLine,
+    /// Text that is only printed if the enclosing group is broken, such as a
+    /// trailing comma.
+    IfBreak(String),
+    Concat(Vec<Doc>),
+    /// Indents the lines that are started inside of it.
+    Nest(Box<Doc>),
+    /// Printed on a single line if it fits, and broken otherwise.
+    Group(Box<Doc>),
+}
+
+/// How documents are laid out.
+#[derive(Clone, Copy, Debug)]
+pub struct PrettyOptions {
+    pub indent_width: usize,
+    pub line_width: usize,
+}
+
+impl Default for PrettyOptions {
+    fn default() -> Self {
+        Self {
+            indent_width: 4,
+            line_width: 80,
+        }
+    }
+}
+
+impl Doc {
+    pub fn text(text: impl Into<String>) -> Self {
+        Doc::Text(text.into())
+    }
+
+    pub fn concat(docs: Vec<Doc>) -> Self {
+        Doc::Concat(docs)
+    }
+
+    pub fn nest(doc: Doc) -> Self {
+        Doc::Nest(Box::new(doc))
+    }
+
+    pub fn group(doc: Doc) -> Self {
+        Doc::Group(Box::new(doc))
+    }
+
+    /// Prints the document, breaking groups that don't fit on their line.
+    pub fn print(&self, options: &PrettyOptions) -> String {
+        let mut output = String::new();
+        let mut column = 0;
+        let mut stack = vec![(0, Mode::Break, self)];
+        while let Some((indent, mode, doc)) = stack.pop() {
+            match doc {
+                Doc::Text(text) => {
+                    output.push_str(text);
+                    column += text.chars().count();
+                }
+                Doc::Line | Doc::SoftLine if mode == Mode::Break => {
+                    // Don't leave trailing whitespace on empty lines.
+                    while output.ends_with(' ') {
+                        output.pop();
+                    }
+                    output.push('\n');
+                    output.push_str(&" ".repeat(indent));
+                    column = indent;
+                }
+                Doc::Line => {
+                    output.push(' ');
+                    column += 1;
+                }
+                Doc::SoftLine => {}
+                Doc::IfBreak(text) => {
+                    if mode == Mode::Break {
+                        output.push_str(text);
+                        column += text.chars().count();
+                    }
+                }
+                Doc::Concat(docs) => {
+                    stack.extend(
+                        docs.iter().rev().map(|doc| (indent, mode, doc)),
+                    );
+                }
+                Doc::Nest(doc) => {
+                    stack.push((indent + options.indent_width, mode, doc));
+                }
+                Doc::Group(doc) => {
+                    let remaining =
+                        options.line_width as isize - column as isize;
+                    let mode =
+                        if mode == Mode::Flat || fits(remaining, doc, &stack) {
+                            Mode::Flat
+                        } else {
+                            Mode::Break
+                        };
+                    stack.push((indent, mode, doc));
+                }
+            }
+        }
+        output
+    }
+
+    /// Prints a document that starts this many indentation levels deep, such
+    /// as a member of a declaration.
+    pub fn print_nested(
+        &self,
+        levels: usize,
+        options: &PrettyOptions,
+    ) -> String {
+        let indent = " ".repeat(levels * options.indent_width);
+        let pretty = PrettyOptions {
+            line_width: options.line_width.saturating_sub(indent.len()),
+            ..*options
+        };
+        self.print(&pretty).replace('\n', &format!("\n{}", indent))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Mode {
+    Flat,
+    Break,
+}
+
+/// Whether a group fits in the remaining width when printed flat, together
+/// with whatever follows it up to the next line break.
+fn fits(
+    mut remaining: isize,
+    group: &Doc,
+    rest: &[(usize, Mode, &Doc)],
+) -> bool {
+    let mut stack = vec![(Mode::Flat, group)];
+    let mut rest = rest.iter().rev();
+    loop {
+        if remaining < 0 {
+            return false;
+        }
+        let (mode, doc) = match stack.pop() {
+            Some(next) => next,
+            None => match rest.next() {
+                Some((_, mode, doc)) => (*mode, *doc),
+                None => return true,
+            },
+        };
+        match doc {
+            Doc::Text(text) => remaining -= text.chars().count() as isize,
+            Doc::Line | Doc::SoftLine if mode == Mode::Break => return true,
+            Doc::Line => remaining -= 1,
+            Doc::SoftLine => {}
+            Doc::IfBreak(text) => {
+                if mode == Mode::Break {
+                    remaining -= text.chars().count() as isize;
+                }
+            }
+            Doc::Concat(docs) => {
+                stack.extend(docs.iter().rev().map(|doc| (mode, doc)))
+            }
+            Doc::Nest(doc) | Doc::Group(doc) => stack.push((mode, doc)),
+        }
+    }
+}
+
+/// A comma separated list between brackets, with one item per line if it
+/// doesn't fit. Padded lists get spaces inside the brackets, like objects.
+pub fn list(open: &str, items: Vec<Doc>, close: &str, padded: bool) -> Doc {
+    separated(open, items, ",", close, padded)
+}
+
+/// A list like [`list`], with another separator between the items, such as
+/// the `;` between the members of a TypeScript type literal.
+pub fn separated(
+    open: &str,
+    items: Vec<Doc>,
+    separator: &str,
+    close: &str,
+    padded: bool,
+) -> Doc {
+    if items.is_empty() {
+        return Doc::text(format!("{}{}", open, close));
+    }
+    let line = || if padded { Doc::Line } else { Doc::SoftLine };
+    let mut inner = vec![line()];
+    for (i, item) in items.into_iter().enumerate() {
+        if i > 0 {
+            inner.push(Doc::text(separator));
+            inner.push(Doc::Line);
+        }
+        inner.push(item);
+    }
+    inner.push(Doc::IfBreak(separator.to_string()));
+    Doc::group(Doc::concat(vec![
+        Doc::text(open),
+        Doc::nest(Doc::concat(inner)),
+        line(),
+        Doc::text(close),
+    ]))
+}
+
+/// A TypeScript union type, with one member per line if it doesn't fit.
+/// Broken unions start on their own line with a leading `|`, like prettier
+/// prints them.
+pub fn union(members: Vec<Doc>) -> Doc {
+    if members.len() <= 1 {
+        return members
+            .into_iter()
+            .next()
+            .unwrap_or_else(|| Doc::text("never"));
+    }
+    let mut inner = vec![Doc::SoftLine, Doc::IfBreak("| ".to_string())];
+    for (i, member) in members.into_iter().enumerate() {
+        if i > 0 {
+            inner.push(Doc::Line);
+            inner.push(Doc::text("| "));
+        }
+        inner.push(member);
+    }
+    Doc::group(Doc::nest(Doc::concat(inner)))
+}
+
+/// An object literal, from keys that are already escaped.
+pub fn object(entries: Vec<(String, Doc)>) -> Doc {
+    let items = entries
+        .into_iter()
+        .map(|(key, value)| {
+            Doc::concat(vec![Doc::text(format!("{}: ", key)), value])
+        })
+        .collect();
+    list("{", items, "}", true)
+}
+
+/// An array literal.
+pub fn array(items: Vec<Doc>) -> Doc {
+    list("[", items, "]", false)
+}
+
+/// A function call. The last argument hugs the parentheses, so objects and
+/// arrays break inside of their own brackets.
+pub fn call(callee: &str, args: Vec<Doc>) -> Doc {
+    let mut docs = vec![Doc::text(format!("{}(", callee))];
+    for (i, arg) in args.into_iter().enumerate() {
+        if i > 0 {
+            docs.push(Doc::text(", "));
+        }
+        docs.push(arg);
+    }
+    docs.push(Doc::text(")"));
+    Doc::concat(docs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options(line_width: usize) -> PrettyOptions {
+        PrettyOptions {
+            indent_width: 2,
+            line_width,
+        }
+    }
+
+    #[test]
+    fn test_groups_break_only_when_they_dont_fit() {
+        let doc = call(
+            "s.struct",
+            vec![object(vec![
+                ("a".to_string(), Doc::text("s.string()")),
+                (
+                    "b".to_string(),
+                    call("s.tuple", vec![array(vec![Doc::text("s.bool()")])]),
+                ),
+            ])],
+        );
+        assert_eq!(
+            doc.print(&options(80)),
+            "s.struct({ a: s.string(), b: s.tuple([s.bool()]) })"
+        );
+        assert_eq!(
+            doc.print(&options(40)),
+            "s.struct({\n  a: s.string(),\n  b: s.tuple([s.bool()]),\n})"
+        );
+        assert_eq!(
+            doc.print(&options(20)),
+            "s.struct({\n  a: s.string(),\n  b: s.tuple([\n    s.bool(),\n  ]),\n})"
+        );
+    }
+
+    #[test]
+    fn test_text_after_a_group_counts_towards_its_width() {
+        let doc = Doc::concat(vec![
+            array(vec![Doc::text("aaaa"), Doc::text("bbbb")]),
+            Doc::text(";;;;"),
+        ]);
+        assert_eq!(doc.print(&options(16)), "[aaaa, bbbb];;;;");
+        assert_eq!(doc.print(&options(15)), "[\n  aaaa,\n  bbbb,\n];;;;");
+    }
+
+    #[test]
+    fn test_unions_break_with_a_leading_bar() {
+        let doc = Doc::concat(vec![
+            Doc::text("type T = "),
+            union(vec![
+                separated("{", vec![Doc::text("a: A")], ";", "}", true),
+                Doc::text("null"),
+            ]),
+            Doc::text(";"),
+        ]);
+        assert_eq!(doc.print(&options(80)), "type T = { a: A } | null;");
+        assert_eq!(
+            doc.print(&options(16)),
+            "type T =\n  | { a: A }\n  | null;"
+        );
+    }
+}
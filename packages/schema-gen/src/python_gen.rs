@@ -0,0 +1,1315 @@
+//! Rendering of the registry as a Python module, for notebooks that work with
+//! the programmatic JSON of the Gateway API. Named types become frozen
+//! dataclasses or type aliases, each with a `parse_<Name>` function that
+//! reads it from programmatic JSON. Decimals are parsed into `Decimal`,
+//! instants into `datetime`, and enums into a union of a dataclass per
+//! variant, tagged with its `variant` name.
+//! The module only depends on the standard library: the parse functions are
+//! built on a small runtime that is emitted at the top of every module.
+
+use crate::ez_mode_gen::{
+    is_inline, reindent, RegistryEntry, SborEzModeSchemaKind, SchemaRegistry,
+    Selection,
+};
+use crate::identifier::{is_identifier_part, sanitize};
+use crate::pretty::{list, Doc};
+use crate::renderer::{RenderOptions, Renderer};
+use crate::well_known::WellKnownType;
+use std::collections::{HashMap, HashSet};
+
+/// Words that can't be used as names in Python.
+const KEYWORDS: &[&str] = &[
+    "False", "None", "True", "and", "as", "assert", "async", "await", "break",
+    "class", "continue", "def", "del", "elif", "else", "except", "finally",
+    "for", "from", "global", "if", "import", "in", "is", "lambda", "nonlocal",
+    "not", "or", "pass", "raise", "return", "try", "while", "with", "yield",
+];
+
+/// Turns a name into a valid Python identifier, see [`sanitize`].
+pub(crate) fn python_identifier(name: &str) -> String {
+    sanitize(name, is_identifier_part, KEYWORDS)
+}
+
+/// Names in the module that are taken by the runtime: its imports, classes
+/// and helpers, and the builtins it uses. `event` is taken by `parse_event`.
+const RUNTIME_NAMES: &[&str] = &[
+    "Any",
+    "Callable",
+    "Decimal",
+    "EVENT_PARSERS",
+    "Err",
+    "Generic",
+    "Literal",
+    "Ok",
+    "Optional",
+    "STATE_PARSERS",
+    "TypeVar",
+    "Union",
+    "UnknownVariant",
+    "ValueError",
+    "_E",
+    "_INTEGER_KINDS",
+    "_Json",
+    "_Parser",
+    "_T",
+    "_address",
+    "_array",
+    "_bool",
+    "_bytes",
+    "_decimal",
+    "_expect",
+    "_field",
+    "_instant",
+    "_int",
+    "_internal_address",
+    "_local_id",
+    "_map",
+    "_named",
+    "_non_fungible_global_id",
+    "_option",
+    "_optional_field",
+    "_result",
+    "_string",
+    "_struct",
+    "_tuple",
+    "_tuple_fields",
+    "_unknown",
+    "_unknown_variant",
+    "_utc_date_time",
+    "_value",
+    "_variant",
+    "annotations",
+    "bool",
+    "bytes",
+    "dataclass",
+    "datetime",
+    "dict",
+    "event",
+    "field",
+    "int",
+    "len",
+    "list",
+    "str",
+    "timezone",
+    "tuple",
+    "zip",
+];
+
+/// The name of the class of a type, clear of the names of the runtime.
+fn class_name(name: &str) -> String {
+    let mut result = python_identifier(name);
+    if RUNTIME_NAMES.contains(&result.as_str()) {
+        result.push('_');
+    }
+    result
+}
+
+/// The curves of the variants of the public key helpers.
+const KEY_CURVES: [&str; 2] = ["Secp256k1", "Ed25519"];
+
+/// The names of the classes in the module.
+struct Classes {
+    /// The class of every entry, indexed like the registry.
+    types: Vec<String>,
+    /// The classes of the variants of every enum, in the order of its
+    /// variants.
+    variants: HashMap<u32, Vec<String>>,
+}
+
+impl Classes {
+    /// Types get their generated name where they can, see [`class_name`].
+    /// The classes of variants are named `{Enum}_{Variant}`, and get a
+    /// trailing `_` while that is taken by a type, the runtime, or another
+    /// variant.
+    fn new(registry: &SchemaRegistry) -> Self {
+        let mut taken: HashSet<String> =
+            RUNTIME_NAMES.iter().map(|name| name.to_string()).collect();
+        for well_known in
+            [WellKnownType::PublicKey, WellKnownType::PublicKeyHash]
+        {
+            for curve in KEY_CURVES {
+                taken.insert(format!("{}_{}", well_known.name(), curve));
+            }
+        }
+        let mut unique = |mut name: String| {
+            while !taken.insert(name.clone()) {
+                name.push('_');
+            }
+            name
+        };
+        let types: Vec<String> = (0..registry.entries.len() as u32)
+            .map(|index| unique(class_name(registry.var_name(index))))
+            .collect();
+        let variants = registry
+            .entries
+            .iter()
+            .enumerate()
+            .filter_map(|(index, entry)| match &entry.schema_kind {
+                SborEzModeSchemaKind::Enum { variants } => Some((
+                    index as u32,
+                    variants
+                        .iter()
+                        .map(|variant| {
+                            unique(format!(
+                                "{}_{}",
+                                types[index],
+                                python_identifier(&variant.name)
+                            ))
+                        })
+                        .collect(),
+                )),
+                _ => None,
+            })
+            .collect();
+        Classes { types, variants }
+    }
+}
+
+/// The name of the member of a field. `variant` is the tag of the
+/// dataclasses of enum variants, so fields of that name are renamed.
+fn member_name(name: &str) -> String {
+    let mut result = python_identifier(name);
+    if result == "variant" {
+        result.push('_');
+    }
+    result
+}
+
+/// A double quoted Python string literal.
+fn python_string(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() => {
+                result.push_str(&format!("\\u{:04x}", c as u32))
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// The runtime of the parse functions, indented by two spaces per level.
+const RUNTIME: &str = r#"from __future__ import annotations
+
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
+from decimal import Decimal
+from typing import Any, Callable, Generic, Literal, Optional, TypeVar, Union
+
+_T = TypeVar("_T")
+_E = TypeVar("_E")
+_Json = dict[str, Any]
+_Parser = Callable[[_Json], Any]
+
+_INTEGER_KINDS = ("I8", "I16", "I32", "I64", "I128", "U8", "U16", "U32", "U64", "U128")
+
+
+@dataclass(frozen=True)
+class Ok(Generic[_T]):
+  value: _T
+  variant: Literal["Ok"] = field(default="Ok", init=False)
+
+
+@dataclass(frozen=True)
+class Err(Generic[_E]):
+  value: _E
+  variant: Literal["Err"] = field(default="Err", init=False)
+
+
+@dataclass(frozen=True)
+class UnknownVariant:
+  """A variant of a lenient enum that isn't in the schema, by its name or id."""
+
+  variant: str
+  value: _Json
+
+
+def _expect(value: _Json, *kinds: str) -> _Json:
+  if value.get("kind") not in kinds:
+    raise ValueError(f"Expected {' or '.join(kinds)}, got {value.get('kind')}")
+  return value
+
+
+def _string(value: _Json) -> str:
+  return _expect(value, "String")["value"]
+
+
+def _bool(value: _Json) -> bool:
+  return _expect(value, "Bool")["value"]
+
+
+def _int(value: _Json) -> int:
+  return int(_expect(value, *_INTEGER_KINDS)["value"])
+
+
+def _decimal(value: _Json) -> Decimal:
+  return Decimal(_expect(value, "Decimal", "PreciseDecimal")["value"])
+
+
+def _address(value: _Json) -> str:
+  return _expect(value, "Reference")["value"]
+
+
+def _internal_address(value: _Json) -> str:
+  return _expect(value, "Own")["value"]
+
+
+def _local_id(value: _Json) -> str:
+  return _expect(value, "NonFungibleLocalId")["value"]
+
+
+def _bytes(value: _Json) -> bytes:
+  return bytes.fromhex(_expect(value, "Bytes")["hex"])
+
+
+def _instant(value: _Json) -> datetime:
+  seconds = int(_expect(value, "I64")["value"])
+  return datetime.fromtimestamp(seconds, tz=timezone.utc)
+
+
+def _value(value: _Json) -> _Json:
+  return value
+
+
+def _array(value: _Json, item: _Parser) -> list[Any]:
+  return [item(element) for element in _expect(value, "Array")["elements"]]
+
+
+def _map(value: _Json, key: _Parser, item: _Parser) -> dict[Any, Any]:
+  entries = _expect(value, "Map")["entries"]
+  return {key(entry["key"]): item(entry["value"]) for entry in entries}
+
+
+def _tuple_fields(fields: list[_Json], items: list[_Parser]) -> tuple[Any, ...]:
+  if len(fields) != len(items):
+    raise ValueError(f"Expected {len(items)} fields, got {len(fields)}")
+  return tuple(item(value) for item, value in zip(items, fields))
+
+
+def _tuple(value: _Json, items: list[_Parser]) -> tuple[Any, ...]:
+  return _tuple_fields(_expect(value, "Tuple")["fields"], items)
+
+
+def _named(fields: list[_Json]) -> dict[str, _Json]:
+  return {value["field_name"]: value for value in fields if "field_name" in value}
+
+
+def _struct(value: _Json) -> dict[str, _Json]:
+  return _named(_expect(value, "Tuple")["fields"])
+
+
+def _field(fields: dict[str, _Json], name: str, item: _Parser) -> Any:
+  if name not in fields:
+    raise ValueError(f"Missing field {name}")
+  return item(fields[name])
+
+
+def _optional_field(fields: dict[str, _Json], name: str, item: _Parser) -> Any:
+  return item(fields[name]) if name in fields else None
+
+
+def _variant(value: _Json) -> tuple[int, list[_Json]]:
+  value = _expect(value, "Enum")
+  return int(value["variant_id"]), value["fields"]
+
+
+def _unknown_variant(value: _Json, type_name: str) -> ValueError:
+  variant = value.get("variant_name") or value["variant_id"]
+  return ValueError(f"Unknown variant {variant} of {type_name}")
+
+
+def _unknown(value: _Json) -> UnknownVariant:
+  return UnknownVariant(str(value.get("variant_name") or value["variant_id"]), value)
+
+
+def _option(value: _Json, some: _Parser) -> Any:
+  variant_id, fields = _variant(value)
+  if variant_id == 0:
+    return None
+  if variant_id == 1:
+    return some(fields[0])
+  raise _unknown_variant(value, "Option")
+
+
+def _result(value: _Json, ok: _Parser, err: _Parser) -> Union[Ok[Any], Err[Any]]:
+  variant_id, fields = _variant(value)
+  if variant_id == 0:
+    return Ok(ok(fields[0]))
+  if variant_id == 1:
+    return Err(err(fields[0]))
+  raise _unknown_variant(value, "Result")
+
+
+def _utc_date_time(value: _Json) -> datetime:
+  year, month, day, hour, minute, second = _tuple(value, [_int] * 6)
+  return datetime(year, month, day, hour, minute, second, tzinfo=timezone.utc)
+
+
+def _non_fungible_global_id(value: _Json) -> tuple[str, str]:
+  return _tuple(value, [_address, _local_id])
+"#;
+
+/// The fields of a variant dataclass.
+enum Payload {
+    Unit,
+    /// The types and parsers of positional fields. A single field is stored
+    /// as `value`, and several as a tuple.
+    Tuple(Vec<(String, String)>),
+    /// The names, types and parsers of named fields.
+    Struct {
+        fields: Vec<(String, String, String)>,
+        lenient: bool,
+    },
+}
+
+/// A variant of an enum, which is declared as its own dataclass.
+struct Variant {
+    discriminator: u8,
+    name: String,
+    class: String,
+    payload: Payload,
+}
+
+/// The members of the dataclass of a struct. Missing fields of lenient
+/// structs are `None`.
+fn struct_members(
+    fields: &[(String, String, String)],
+    lenient: bool,
+) -> Vec<(String, String)> {
+    fields
+        .iter()
+        .map(|(name, field_type, _)| {
+            let field_type = if lenient {
+                format!("Optional[{}]", field_type)
+            } else {
+                field_type.clone()
+            };
+            (member_name(name), field_type)
+        })
+        .collect()
+}
+
+/// Renders the registry as a Python module of dataclasses.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PythonRenderer;
+
+impl PythonRenderer {
+    /// The Python type of a reference to an entry.
+    fn type_of(
+        &self,
+        registry: &SchemaRegistry,
+        classes: &Classes,
+        index: u32,
+    ) -> String {
+        let entry = &registry.entries[index as usize];
+        if is_inline(entry) {
+            self.structural_type(registry, classes, entry)
+        } else {
+            classes.types[index as usize].clone()
+        }
+    }
+
+    /// The Python type of an entry that isn't a struct or enum.
+    fn structural_type(
+        &self,
+        registry: &SchemaRegistry,
+        classes: &Classes,
+        entry: &RegistryEntry,
+    ) -> String {
+        let child = |index: u32| self.type_of(registry, classes, index);
+        match &entry.schema_kind {
+            SborEzModeSchemaKind::Address { .. }
+            | SborEzModeSchemaKind::InternalAddress { .. }
+            | SborEzModeSchemaKind::String
+            | SborEzModeSchemaKind::NonFungibleLocalId => "str".to_string(),
+            SborEzModeSchemaKind::Decimal
+            | SborEzModeSchemaKind::PreciseDecimal => "Decimal".to_string(),
+            SborEzModeSchemaKind::Integer { .. } => "int".to_string(),
+            SborEzModeSchemaKind::Bool => "bool".to_string(),
+            SborEzModeSchemaKind::Bytes => "bytes".to_string(),
+            SborEzModeSchemaKind::Value => "_Json".to_string(),
+            SborEzModeSchemaKind::WellKnown(well_known) => match well_known {
+                WellKnownType::Instant | WellKnownType::UtcDateTime => {
+                    "datetime".to_string()
+                }
+                WellKnownType::NonFungibleGlobalId => {
+                    "tuple[str, str]".to_string()
+                }
+                WellKnownType::PublicKey | WellKnownType::PublicKeyHash => {
+                    well_known.name().to_string()
+                }
+                _ => "bytes".to_string(),
+            },
+            SborEzModeSchemaKind::Array { element_type } => {
+                format!("list[{}]", child(*element_type))
+            }
+            SborEzModeSchemaKind::Map {
+                key_type,
+                value_type,
+            } => format!("dict[{}, {}]", child(*key_type), child(*value_type)),
+            SborEzModeSchemaKind::Tuple { fields } if fields.is_empty() => {
+                "tuple[()]".to_string()
+            }
+            SborEzModeSchemaKind::Tuple { fields } => format!(
+                "tuple[{}]",
+                fields
+                    .iter()
+                    .map(|&field| child(field))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            SborEzModeSchemaKind::Option { some } => {
+                format!("Optional[{}]", child(*some))
+            }
+            SborEzModeSchemaKind::Result { ok, err } => {
+                format!("Union[Ok[{}], Err[{}]]", child(*ok), child(*err))
+            }
+            kind => unreachable!("{:?} is declared as a dataclass", kind),
+        }
+    }
+
+    /// The function that parses a reference to an entry.
+    fn parser_of(
+        &self,
+        registry: &SchemaRegistry,
+        classes: &Classes,
+        index: u32,
+    ) -> String {
+        let entry = &registry.entries[index as usize];
+        if !is_inline(entry) {
+            return format!("parse_{}", classes.types[index as usize]);
+        }
+        match self.runtime_parser(entry) {
+            Some(parser) => parser,
+            None => {
+                format!(
+                    "lambda v: {}",
+                    self.parse_call(registry, classes, entry, "v")
+                )
+            }
+        }
+    }
+
+    /// The function of the runtime that parses an entry, for the entries
+    /// that don't refer to other entries.
+    fn runtime_parser(&self, entry: &RegistryEntry) -> Option<String> {
+        let parser = match &entry.schema_kind {
+            SborEzModeSchemaKind::Address { .. } => "_address",
+            SborEzModeSchemaKind::InternalAddress { .. } => "_internal_address",
+            SborEzModeSchemaKind::String => "_string",
+            SborEzModeSchemaKind::NonFungibleLocalId => "_local_id",
+            SborEzModeSchemaKind::Decimal
+            | SborEzModeSchemaKind::PreciseDecimal => "_decimal",
+            SborEzModeSchemaKind::Integer { .. } => "_int",
+            SborEzModeSchemaKind::Bool => "_bool",
+            SborEzModeSchemaKind::Bytes => "_bytes",
+            SborEzModeSchemaKind::Value => "_value",
+            SborEzModeSchemaKind::WellKnown(well_known) => match well_known {
+                WellKnownType::Instant => "_instant",
+                WellKnownType::UtcDateTime => "_utc_date_time",
+                WellKnownType::NonFungibleGlobalId => "_non_fungible_global_id",
+                WellKnownType::PublicKey | WellKnownType::PublicKeyHash => {
+                    return Some(format!("parse_{}", well_known.name()));
+                }
+                _ => "_bytes",
+            },
+            _ => return None,
+        };
+        Some(parser.to_string())
+    }
+
+    /// An expression that parses `argument` as an entry that isn't a struct
+    /// or enum.
+    fn parse_call(
+        &self,
+        registry: &SchemaRegistry,
+        classes: &Classes,
+        entry: &RegistryEntry,
+        argument: &str,
+    ) -> String {
+        if let Some(parser) = self.runtime_parser(entry) {
+            return format!("{}({})", parser, argument);
+        }
+        let child = |index: u32| self.parser_of(registry, classes, index);
+        match &entry.schema_kind {
+            SborEzModeSchemaKind::Array { element_type } => {
+                format!("_array({}, {})", argument, child(*element_type))
+            }
+            SborEzModeSchemaKind::Map {
+                key_type,
+                value_type,
+            } => format!(
+                "_map({}, {}, {})",
+                argument,
+                child(*key_type),
+                child(*value_type)
+            ),
+            SborEzModeSchemaKind::Tuple { fields } => format!(
+                "_tuple({}, [{}])",
+                argument,
+                fields
+                    .iter()
+                    .map(|&field| child(field))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ),
+            SborEzModeSchemaKind::Option { some } => {
+                format!("_option({}, {})", argument, child(*some))
+            }
+            SborEzModeSchemaKind::Result { ok, err } => format!(
+                "_result({}, {}, {})",
+                argument,
+                child(*ok),
+                child(*err)
+            ),
+            kind => unreachable!("{:?} is declared as a dataclass", kind),
+        }
+    }
+
+    /// The names, types and parsers of the fields of a struct.
+    fn struct_fields(
+        &self,
+        registry: &SchemaRegistry,
+        classes: &Classes,
+        fields: &[(String, u32)],
+    ) -> Vec<(String, String, String)> {
+        fields
+            .iter()
+            .map(|(name, field)| {
+                (
+                    name.clone(),
+                    self.type_of(registry, classes, *field),
+                    self.parser_of(registry, classes, *field),
+                )
+            })
+            .collect()
+    }
+
+    /// Returns a dataclass with the named fields read from `fields_var`.
+    fn construct_struct(
+        &self,
+        class: &str,
+        fields: &[(String, String, String)],
+        lenient: bool,
+        fields_var: &str,
+    ) -> Doc {
+        let getter = if lenient { "_optional_field" } else { "_field" };
+        let arguments = fields
+            .iter()
+            .map(|(name, _, parser)| {
+                Doc::text(format!(
+                    "{}={}({}, {}, {})",
+                    member_name(name),
+                    getter,
+                    fields_var,
+                    python_string(name),
+                    parser
+                ))
+            })
+            .collect();
+        Doc::concat(vec![
+            Doc::text("return "),
+            list(&format!("{}(", class), arguments, ")", false),
+        ])
+    }
+
+    /// Declares a frozen dataclass, tagged with its name for variants.
+    fn dataclass(
+        &self,
+        class: &str,
+        members: &[(String, String)],
+        variant: Option<&str>,
+        options: &RenderOptions,
+    ) -> String {
+        let indent = " ".repeat(options.pretty.indent_width);
+        let mut body: String = members
+            .iter()
+            .map(|(name, member_type)| {
+                format!("{}{}: {}\n", indent, name, member_type)
+            })
+            .collect();
+        if let Some(variant) = variant {
+            let variant = python_string(variant);
+            body.push_str(&format!(
+                "{}variant: Literal[{}] = field(default={}, init=False)\n",
+                indent, variant, variant
+            ));
+        }
+        if body.is_empty() {
+            body = format!("{}pass\n", indent);
+        }
+        format!("@dataclass(frozen=True)\nclass {}:\n{}\n\n", class, body)
+    }
+
+    /// Declares the parse function of a type, from the statements of its
+    /// body by how deep they are nested.
+    fn parse_function(
+        &self,
+        name: &str,
+        statements: Vec<(usize, Doc)>,
+        options: &RenderOptions,
+    ) -> String {
+        let body: String = statements
+            .into_iter()
+            .map(|(level, statement)| {
+                format!(
+                    "{}{}\n",
+                    " ".repeat((level + 1) * options.pretty.indent_width),
+                    statement.print_nested(level + 1, &options.pretty)
+                )
+            })
+            .collect();
+        format!(
+            "def parse_{}(value: _Json) -> {}:\n{}\n\n",
+            name, name, body
+        )
+    }
+
+    /// Declares a dataclass per variant, their union, and a parse function
+    /// that picks the variant by its discriminator.
+    fn enum_declaration(
+        &self,
+        name: &str,
+        variants: &[Variant],
+        lenient: bool,
+        options: &RenderOptions,
+    ) -> String {
+        let mut output = String::new();
+        let mut classes = Vec::new();
+        let mut statements =
+            vec![(0, Doc::text("variant_id, fields = _variant(value)"))];
+        for variant in variants {
+            let class = &variant.class;
+            statements.push((
+                0,
+                Doc::text(format!(
+                    "if variant_id == {}:",
+                    variant.discriminator
+                )),
+            ));
+            let members = match &variant.payload {
+                Payload::Unit => {
+                    statements
+                        .push((1, Doc::text(format!("return {}()", class))));
+                    Vec::new()
+                }
+                Payload::Tuple(fields) => {
+                    let parsers = fields
+                        .iter()
+                        .map(|(_, parser)| parser.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    let types = fields
+                        .iter()
+                        .map(|(field_type, _)| field_type.as_str())
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                    // A single field is unpacked out of its tuple.
+                    let (value_type, unpack) = if fields.len() == 1 {
+                        (types, "*")
+                    } else {
+                        (format!("tuple[{}]", types), "")
+                    };
+                    statements.push((
+                        1,
+                        Doc::text(format!(
+                            "return {}({}_tuple_fields(fields, [{}]))",
+                            class, unpack, parsers
+                        )),
+                    ));
+                    vec![("value".to_string(), value_type)]
+                }
+                Payload::Struct { fields, lenient } => {
+                    statements.push((1, Doc::text("named = _named(fields)")));
+                    statements.push((
+                        1,
+                        self.construct_struct(class, fields, *lenient, "named"),
+                    ));
+                    struct_members(fields, *lenient)
+                }
+            };
+            output.push_str(&self.dataclass(
+                class,
+                &members,
+                Some(&variant.name),
+                options,
+            ));
+            classes.push(Doc::text(class.clone()));
+        }
+        statements.push((
+            0,
+            Doc::text(if lenient {
+                "return _unknown(value)".to_string()
+            } else {
+                format!(
+                    "raise _unknown_variant(value, {})",
+                    python_string(name)
+                )
+            }),
+        ));
+        if lenient {
+            classes.push(Doc::text("UnknownVariant"));
+        }
+
+        let union = Doc::concat(vec![
+            Doc::text(format!("{} = ", name)),
+            list("Union[", classes, "]", false),
+        ]);
+        output.push_str(&format!("{}\n\n\n", union.print(&options.pretty)));
+        output.push_str(&self.parse_function(name, statements, options));
+        output
+    }
+
+    /// The fields of a variant, from its payload entry.
+    fn payload(
+        &self,
+        registry: &SchemaRegistry,
+        classes: &Classes,
+        payload: u32,
+        options: &RenderOptions,
+    ) -> Payload {
+        let entry = &registry.entries[payload as usize];
+        match &entry.schema_kind {
+            SborEzModeSchemaKind::Struct { fields } => Payload::Struct {
+                fields: self.struct_fields(registry, classes, fields),
+                lenient: registry.is_lenient(entry, options),
+            },
+            SborEzModeSchemaKind::Tuple { fields } if fields.is_empty() => {
+                Payload::Unit
+            }
+            SborEzModeSchemaKind::Tuple { fields } => Payload::Tuple(
+                fields
+                    .iter()
+                    .map(|&field| {
+                        (
+                            self.type_of(registry, classes, field),
+                            self.parser_of(registry, classes, field),
+                        )
+                    })
+                    .collect(),
+            ),
+            kind => unreachable!("{:?} is not a variant payload", kind),
+        }
+    }
+
+    /// Declares a named entry together with its parse function.
+    fn declaration(
+        &self,
+        registry: &SchemaRegistry,
+        classes: &Classes,
+        index: u32,
+        options: &RenderOptions,
+    ) -> String {
+        let entry = &registry.entries[index as usize];
+        let name = classes.types[index as usize].clone();
+        let lenient = registry.is_lenient(entry, options);
+        match &entry.schema_kind {
+            SborEzModeSchemaKind::Struct { fields } => {
+                let fields = self.struct_fields(registry, classes, fields);
+                let statements = vec![
+                    (0, Doc::text("fields = _struct(value)")),
+                    (
+                        0,
+                        self.construct_struct(
+                            &name, &fields, lenient, "fields",
+                        ),
+                    ),
+                ];
+                format!(
+                    "{}{}",
+                    self.dataclass(
+                        &name,
+                        &struct_members(&fields, lenient),
+                        None,
+                        options
+                    ),
+                    self.parse_function(&name, statements, options)
+                )
+            }
+            SborEzModeSchemaKind::Enum { variants } => {
+                let variants: Vec<Variant> = variants
+                    .iter()
+                    .zip(&classes.variants[&index])
+                    .map(|(variant, class)| Variant {
+                        discriminator: variant.discriminator,
+                        name: variant.name.clone(),
+                        class: class.clone(),
+                        payload: match variant.payload {
+                            Some(payload) => self
+                                .payload(registry, classes, payload, options),
+                            None => Payload::Unit,
+                        },
+                    })
+                    .collect();
+                self.enum_declaration(&name, &variants, lenient, options)
+            }
+            // Named tuples, and inline types that are selected by name.
+            _ => {
+                let statement = Doc::text(format!(
+                    "return {}",
+                    self.parse_call(registry, classes, entry, "value")
+                ));
+                format!(
+                    "{} = {}\n\n\n{}",
+                    name,
+                    self.structural_type(registry, classes, entry),
+                    self.parse_function(&name, vec![(0, statement)], options)
+                )
+            }
+        }
+    }
+
+    /// Declares the helpers of the public key types, which are enums of a
+    /// variant per curve.
+    fn well_known_helper(
+        &self,
+        well_known: WellKnownType,
+        options: &RenderOptions,
+    ) -> Option<String> {
+        let key_variant = |discriminator: u8, name: &str| Variant {
+            discriminator,
+            name: name.to_string(),
+            class: format!("{}_{}", well_known.name(), name),
+            payload: Payload::Tuple(vec![(
+                "bytes".to_string(),
+                "_bytes".to_string(),
+            )]),
+        };
+        match well_known {
+            WellKnownType::PublicKey | WellKnownType::PublicKeyHash => {
+                Some(self.enum_declaration(
+                    well_known.name(),
+                    &[
+                        key_variant(0, KEY_CURVES[0]),
+                        key_variant(1, KEY_CURVES[1]),
+                    ],
+                    false,
+                    options,
+                ))
+            }
+            _ => None,
+        }
+    }
+
+    /// Declares the parsers of the states and events of the blueprints, and
+    /// a `parse_event` function that looks them up.
+    fn blueprint_parsers(
+        &self,
+        registry: &SchemaRegistry,
+        classes: &Classes,
+        blueprints: &[crate::ez_mode_gen::BlueprintRoots],
+        options: &RenderOptions,
+    ) -> String {
+        let indent = " ".repeat(options.pretty.indent_width);
+        let states: String = blueprints
+            .iter()
+            .filter_map(|roots| {
+                roots.state.map(|state| {
+                    format!(
+                        "{}{}: {},\n",
+                        indent,
+                        python_string(&roots.blueprint),
+                        self.parser_of(registry, classes, state)
+                    )
+                })
+            })
+            .collect();
+        let events: String = blueprints
+            .iter()
+            .flat_map(|roots| {
+                roots.events.iter().map(|(name, index)| {
+                    format!(
+                        "{}({}, {}): {},\n",
+                        indent,
+                        python_string(&roots.blueprint),
+                        python_string(name),
+                        self.parser_of(registry, classes, *index)
+                    )
+                })
+            })
+            .collect();
+        let parse_event = r#"def parse_event(
+  emitter_blueprint: str, event_name: str, programmatic_json: _Json
+) -> Any:
+  """Parses the payload of an event, based on the blueprint that emitted it
+  and the name of the event. Returns None for events of other packages."""
+  parser = EVENT_PARSERS.get((emitter_blueprint, event_name))
+  return None if parser is None else parser(programmatic_json)
+"#;
+        format!(
+            "STATE_PARSERS: dict[str, _Parser] = {{\n{}}}\n\nEVENT_PARSERS: dict[tuple[str, str], _Parser] = {{\n{}}}\n\n\n{}",
+            states,
+            events,
+            reindent(parse_event, options.pretty.indent_width)
+        )
+    }
+}
+
+impl Renderer for PythonRenderer {
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let Selection {
+            blueprints,
+            types,
+            reachable,
+        } = registry.select(&options.filter);
+        let mut output = format!(
+            "# Generated Python types for Scrypto SBOR types of package address: {}\n# Parse the programmatic JSON of the Gateway API with parse_event, or with\n# the parse_ function of a type.\n\n{}\n\n",
+            package_address,
+            reindent(RUNTIME, options.pretty.indent_width)
+        );
+
+        let mut well_known: Vec<WellKnownType> = reachable
+            .iter()
+            .filter_map(|&i| match registry.entries[i as usize].schema_kind {
+                SborEzModeSchemaKind::WellKnown(well_known) => Some(well_known),
+                _ => None,
+            })
+            .collect();
+        well_known.sort_by_key(|well_known| well_known.name());
+        well_known.dedup();
+        for well_known in well_known {
+            if let Some(helper) = self.well_known_helper(well_known, options) {
+                output.push_str(&helper);
+            }
+        }
+
+        let classes = Classes::new(registry);
+        // Variant payloads are declared as part of their enum.
+        let payloads: HashSet<u32> = reachable
+            .iter()
+            .flat_map(|&i| match &registry.entries[i as usize].schema_kind {
+                SborEzModeSchemaKind::Enum { variants } => variants
+                    .iter()
+                    .filter_map(|variant| variant.payload)
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+        let sorted_indices = registry
+            .topologically_sorted_indices()
+            .unwrap_or_else(|err| {
+                panic!("Error performing topological sort: {}", err);
+            });
+        for i in sorted_indices {
+            let entry = &registry.entries[i as usize];
+            // Inline types are only declared when they are selected by name.
+            if !reachable.contains(&i)
+                || payloads.contains(&i)
+                || (is_inline(entry) && !types.contains(&i))
+            {
+                continue;
+            }
+            output.push_str(&self.declaration(registry, &classes, i, options));
+        }
+        output.push_str(&self.blueprint_parsers(
+            registry,
+            &classes,
+            &blueprints,
+            options,
+        ));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{
+        testing::entry, BlueprintRoots, EnumVariant, ReferenceKind,
+    };
+    use crate::naming::RenameMap;
+    use crate::well_known::WellKnownType;
+
+    fn variant(
+        discriminator: u8,
+        name: &str,
+        payload: Option<u32>,
+    ) -> EnumVariant {
+        EnumVariant {
+            discriminator,
+            name: name.to_string(),
+            payload,
+        }
+    }
+
+    #[test]
+    fn test_identifiers_are_escaped() {
+        assert_eq!(python_identifier("HashMap<K, V>"), "HashMap_K_V");
+        assert_eq!(python_identifier("0th"), "_0th");
+        assert_eq!(python_identifier("class"), "class_");
+        assert_eq!(python_identifier("None"), "None_");
+        assert_eq!(python_identifier("my-field"), "my_field");
+        assert_eq!(class_name("Ok"), "Ok_");
+        assert_eq!(class_name("event"), "event_");
+        assert_eq!(member_name("variant"), "variant_");
+        assert_eq!(member_name("value"), "value");
+    }
+
+    #[test]
+    fn test_names_dont_shadow_the_runtime() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::String),
+            entry(
+                None,
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("variant".to_string(), 0)],
+                },
+            ),
+            entry(
+                Some("Ok"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![EnumVariant {
+                        discriminator: 0,
+                        name: "Renamed".to_string(),
+                        payload: Some(1),
+                    }],
+                },
+            ),
+            entry(
+                Some("SwapEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("result".to_string(), 2)],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: None,
+            events: vec![("SwapEvent".to_string(), 3)],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output = PythonRenderer.render(
+            &registry,
+            "package_pool",
+            &Default::default(),
+        );
+
+        assert!(output.contains(
+            "class Ok__Renamed:\n    variant_: str\n    \
+             variant: Literal[\"Renamed\"]"
+        ));
+        assert!(output.contains(
+            "return Ok__Renamed(variant_=_field(named, \"variant\", _string))"
+        ));
+        assert!(output.contains("Ok_ = Union[Ok__Renamed]"));
+        assert!(output.contains("    result: Ok_\n"));
+    }
+
+    #[test]
+    fn test_variant_classes_dont_clash_with_types() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::String),
+            entry(
+                Some("Fee"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![variant(0, "Low", None)],
+                },
+            ),
+            entry(
+                Some("Fee_Low"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("reason".to_string(), 0)],
+                },
+            ),
+            entry(
+                Some("_Json"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("raw".to_string(), 0)],
+                },
+            ),
+            entry(
+                Some("SwapEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("fee".to_string(), 1),
+                        ("low".to_string(), 2),
+                        ("json".to_string(), 3),
+                    ],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: None,
+            events: vec![("SwapEvent".to_string(), 4)],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output = PythonRenderer.render(
+            &registry,
+            "package_pool",
+            &Default::default(),
+        );
+
+        assert!(output.contains("class Fee_Low:\n    reason: str\n"));
+        assert!(
+            output.contains("class Fee_Low_:\n    variant: Literal[\"Low\"]")
+        );
+        assert!(output.contains("Fee = Union[Fee_Low_]"));
+        assert!(output.contains("class _Json_:\n    raw: str\n"));
+        assert!(output
+            .contains("    fee: Fee\n    low: Fee_Low\n    json: _Json_\n"));
+    }
+
+    /// Every name that the runtime declares has to be in `RUNTIME_NAMES`.
+    #[test]
+    fn test_runtime_names_are_complete() {
+        for line in RUNTIME.lines() {
+            let declaration = line
+                .strip_prefix("def ")
+                .or_else(|| line.strip_prefix("class "))
+                .unwrap_or(line);
+            let end = declaration
+                .find(|c: char| !is_identifier_part(c))
+                .unwrap_or(declaration.len());
+            let (name, rest) = declaration.split_at(end);
+            if declaration != line || rest.starts_with(" = ") {
+                assert!(RUNTIME_NAMES.contains(&name), "{} is missing", name);
+            }
+        }
+    }
+
+    /// The output for a small pool package is checked in, so that changes to
+    /// it show up in review. Run with `UPDATE_FIXTURES=1` to regenerate it.
+    #[test]
+    fn test_pool_renders_python() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::Address {
+                    reference: ReferenceKind::Resource,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 64,
+                    signed: false,
+                },
+            ),
+            entry(None, SborEzModeSchemaKind::String),
+            entry(
+                Some("SwapEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("input_amount".to_string(), 0),
+                        ("input_resource".to_string(), 1),
+                        ("output_amount".to_string(), 0),
+                        ("type".to_string(), 3),
+                    ],
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Map {
+                    key_type: 1,
+                    value_type: 0,
+                },
+            ),
+            entry(None, SborEzModeSchemaKind::Tuple { fields: vec![0] }),
+            entry(None, SborEzModeSchemaKind::Array { element_type: 0 }),
+            entry(
+                None,
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("steps".to_string(), 7)],
+                },
+            ),
+            entry(
+                Some("FeeTier"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![
+                        variant(0, "Low", None),
+                        variant(1, "Custom", Some(6)),
+                        variant(3, "Stepped", Some(8)),
+                    ],
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::WellKnown(
+                    WellKnownType::NonFungibleGlobalId,
+                ),
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::WellKnown(WellKnownType::Instant),
+            ),
+            entry(Some("Option"), SborEzModeSchemaKind::Option { some: 11 }),
+            entry(
+                Some("PoolState"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("reserves".to_string(), 5),
+                        ("fee_tier".to_string(), 9),
+                        ("admin_badge".to_string(), 10),
+                        ("last_swap".to_string(), 12),
+                        ("swap_count".to_string(), 2),
+                        ("prices".to_string(), 16),
+                    ],
+                },
+            ),
+            entry(
+                Some("PriceUpdate"),
+                SborEzModeSchemaKind::Tuple { fields: vec![3, 2] },
+            ),
+            entry(
+                Some("PairKey"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("base".to_string(), 1),
+                        ("quote".to_string(), 1),
+                    ],
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Map {
+                    key_type: 15,
+                    value_type: 0,
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(13),
+            events: vec![
+                ("SwapEvent".to_string(), 4),
+                ("PriceUpdate".to_string(), 14),
+            ],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output = PythonRenderer.render(
+            &registry,
+            "package_pool",
+            &Default::default(),
+        );
+
+        let fixture = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/pool.generated.py"
+        );
+        if std::env::var_os("UPDATE_FIXTURES").is_some() {
+            std::fs::write(fixture, &output).unwrap();
+        }
+        assert_eq!(output, std::fs::read_to_string(fixture).unwrap());
+    }
+}
@@ -0,0 +1,137 @@
+//! Rendering of the intermediate representation into an output format.
+//! Every output format implements [`Renderer`] over the public
+//! [`SchemaRegistry`], so crates that depend on this one can add their own
+//! formats next to the built-in ones.
+
+use crate::doc::DocRenderer;
+use crate::dts::DtsRenderer;
+use crate::extern_blueprint::ExternBlueprintRenderer;
+use crate::ez_mode_gen::{
+    EzModeRenderer, LenientConfig, SchemaRegistry, WideIntegerMode,
+};
+use crate::ir::IrJsonRenderer;
+use crate::json_schema::JsonSchemaRenderer;
+use crate::pretty::PrettyOptions;
+use crate::python_gen::PythonRenderer;
+use crate::rust_gen::RustRenderer;
+use crate::sql::SqlRenderer;
+use std::collections::HashSet;
+
+/// Renders the types of a package from its registry.
+pub trait Renderer {
+    /// Renders the registry into the source of a single file.
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String;
+}
+
+/// The built-in output formats.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    PartialEq,
+    Eq,
+    clap::ValueEnum,
+    serde::Deserialize,
+)]
+#[serde(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// sbor-ez-mode TypeScript schemas.
+    #[default]
+    EzMode,
+    /// TypeScript types of the parsed values, without the schemas.
+    Dts,
+    /// A JSON Schema (draft 2020-12) of the parsed values.
+    JsonSchema,
+    /// Rust types deriving `ScryptoSbor`.
+    Rust,
+    /// `extern_blueprint!` declarations for calling the package.
+    ExternBlueprint,
+    /// Python dataclasses parsed from programmatic JSON.
+    Python,
+    /// Postgres tables for indexing the events and state.
+    Sql,
+    /// A Markdown reference of the blueprints and their types.
+    Doc,
+    /// The intermediate representation as versioned JSON, which can be
+    /// rendered later with `--from-ir`.
+    IrJson,
+}
+
+impl OutputFormat {
+    /// The renderer of this format.
+    pub fn renderer(&self) -> Box<dyn Renderer> {
+        match self {
+            OutputFormat::EzMode => Box::new(EzModeRenderer),
+            OutputFormat::Dts => Box::new(DtsRenderer),
+            OutputFormat::JsonSchema => Box::new(JsonSchemaRenderer),
+            OutputFormat::Rust => Box::new(RustRenderer::default()),
+            OutputFormat::ExternBlueprint => Box::new(ExternBlueprintRenderer),
+            OutputFormat::Python => Box::new(PythonRenderer),
+            OutputFormat::Sql => Box::new(SqlRenderer),
+            OutputFormat::Doc => Box::new(DocRenderer),
+            OutputFormat::IrJson => Box::new(IrJsonRenderer),
+        }
+    }
+
+    /// Warnings about rendering the registry in this format. Renderers don't
+    /// print them, so that every caller can report them its own way.
+    pub fn warnings(
+        &self,
+        registry: &SchemaRegistry,
+        options: &RenderOptions,
+    ) -> Vec<String> {
+        let mut warnings = registry.unknown_type_warnings(&options.filter);
+        if matches!(self, OutputFormat::EzMode | OutputFormat::Dts) {
+            warnings.extend(registry.lossy_integer_warnings(options));
+        }
+        warnings
+    }
+}
+
+/// Options that influence how the registry is rendered. Formats ignore the
+/// options that don't apply to them, such as `module` outside of TypeScript.
+#[derive(Clone, Debug, Default)]
+pub struct RenderOptions {
+    /// Render as a ready-to-use module with export and import syntax.
+    pub module: bool,
+    pub wide_integers: WideIntegerMode,
+    pub lenient: LenientConfig,
+    /// The indent width and line length of the generated code.
+    pub pretty: PrettyOptions,
+    pub filter: RootFilter,
+    /// Declare the types of the parsed values next to the sbor-ez-mode
+    /// schemas, under the same names.
+    pub with_types: bool,
+}
+
+/// Which roots are rendered. Only the types that are reachable from the
+/// selected roots are emitted.
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct RootFilter {
+    /// The blueprints to render. When empty, every blueprint is rendered,
+    /// unless specific types are selected.
+    pub blueprints: HashSet<String>,
+    /// Types to render by their generated name, next to the blueprints.
+    pub types: HashSet<String>,
+    /// Leave out the state of the blueprints.
+    pub events_only: bool,
+    /// Leave out the events of the blueprints.
+    pub state_only: bool,
+}
+
+impl RootFilter {
+    pub fn includes_blueprint(&self, blueprint: &str) -> bool {
+        if self.blueprints.is_empty() {
+            self.types.is_empty()
+        } else {
+            self.blueprints.contains(blueprint)
+        }
+    }
+}
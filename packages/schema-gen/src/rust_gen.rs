@@ -0,0 +1,566 @@
+//! Rendering of the registry as Rust types, for services that decode the
+//! same events and state off-ledger. Every type derives `ScryptoSbor`, so
+//! payloads can be decoded with `scrypto_decode` directly. The types refer to
+//! `Decimal`, `ResourceAddress` and friends from the scrypto prelude.
+
+use crate::ez_mode_gen::{
+    is_inline, OwnKind, ReferenceKind, RegistryEntry, SborEzModeSchemaKind,
+    SchemaRegistry, Selection,
+};
+use crate::identifier::{is_identifier_part, sanitize};
+use crate::pretty::{list, Doc};
+use crate::renderer::{RenderOptions, Renderer};
+use std::collections::HashSet;
+
+/// Keywords that are escaped as raw identifiers.
+const KEYWORDS: &[&str] = &[
+    "abstract", "as", "async", "await", "become", "box", "break", "const",
+    "continue", "do", "dyn", "else", "enum", "extern", "false", "final", "fn",
+    "for", "gen", "if", "impl", "in", "let", "loop", "macro", "match", "mod",
+    "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized",
+    "use", "virtual", "where", "while", "yield",
+];
+
+/// Keywords that can't be raw identifiers, and `_`, which get a trailing `_`
+/// instead.
+const STRICT_KEYWORDS: &[&str] = &["_", "crate", "self", "Self", "super"];
+
+/// Turns a name into a valid Rust identifier, see [`sanitize`].
+pub(crate) fn rust_identifier(name: &str) -> String {
+    let result = sanitize(name, is_identifier_part, STRICT_KEYWORDS);
+    if KEYWORDS.contains(&result.as_str()) {
+        format!("r#{}", result)
+    } else {
+        result
+    }
+}
+
+/// Renders the registry as Rust types that derive `ScryptoSbor`.
+#[derive(Clone, Debug, Default)]
+pub struct RustRenderer {
+    /// Render owned entities as `Bucket`, `Proof` and so on. These only
+    /// exist inside of the engine, so this is for the arguments of calls
+    /// rather than for decoding.
+    pub engine_types: bool,
+    /// Blueprints declared with `extern_blueprint!` next to the types, which
+    /// are referred to as `Global<Blueprint>` and `Owned<Blueprint>`.
+    pub extern_blueprints: HashSet<String>,
+}
+
+impl RustRenderer {
+    /// The Rust type of a reference to an entry.
+    pub fn type_of(&self, registry: &SchemaRegistry, index: u32) -> String {
+        let entry = &registry.entries[index as usize];
+        if is_inline(entry) {
+            self.inline_type(registry, entry)
+        } else {
+            rust_identifier(registry.var_name(index))
+        }
+    }
+
+    fn inline_type(
+        &self,
+        registry: &SchemaRegistry,
+        entry: &RegistryEntry,
+    ) -> String {
+        let child = |index: u32| self.type_of(registry, index);
+        match &entry.schema_kind {
+            SborEzModeSchemaKind::Tuple { fields } => match fields.as_slice() {
+                [field] => format!("({},)", child(*field)),
+                fields => format!(
+                    "({})",
+                    fields
+                        .iter()
+                        .map(|&field| child(field))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            },
+            SborEzModeSchemaKind::Array { element_type } => {
+                format!("Vec<{}>", child(*element_type))
+            }
+            SborEzModeSchemaKind::Map {
+                key_type,
+                value_type,
+            } => {
+                format!(
+                    "IndexMap<{}, {}>",
+                    child(*key_type),
+                    child(*value_type)
+                )
+            }
+            SborEzModeSchemaKind::Option { some } => {
+                format!("Option<{}>", child(*some))
+            }
+            SborEzModeSchemaKind::Result { ok, err } => {
+                format!("Result<{}, {}>", child(*ok), child(*err))
+            }
+            SborEzModeSchemaKind::Decimal
+            | SborEzModeSchemaKind::PreciseDecimal => entry
+                .schema_kind
+                .decimal_precision()
+                .unwrap()
+                .name
+                .to_string(),
+            SborEzModeSchemaKind::Address {
+                reference:
+                    ReferenceKind::GlobalTyped {
+                        package: None,
+                        blueprint,
+                    },
+            } if self.extern_blueprints.contains(blueprint) => {
+                format!("Global<{}>", blueprint)
+            }
+            SborEzModeSchemaKind::Address { reference } => match reference {
+                ReferenceKind::Any => "Reference",
+                ReferenceKind::Global | ReferenceKind::GlobalTyped { .. } => {
+                    "GlobalAddress"
+                }
+                ReferenceKind::Package => "PackageAddress",
+                ReferenceKind::Component => "ComponentAddress",
+                ReferenceKind::Resource => "ResourceAddress",
+                ReferenceKind::Internal
+                | ReferenceKind::InternalTyped { .. } => "InternalAddress",
+            }
+            .to_string(),
+            // Outside of the engine, owned entities can only be decoded as an
+            // `Own`, as a `Vault` or `Bucket` needs a running engine.
+            SborEzModeSchemaKind::InternalAddress { own }
+                if self.engine_types =>
+            {
+                match own {
+                    OwnKind::Bucket => "Bucket".to_string(),
+                    OwnKind::Proof => "Proof".to_string(),
+                    OwnKind::Vault => "Vault".to_string(),
+                    OwnKind::GlobalAddressReservation => {
+                        "GlobalAddressReservation".to_string()
+                    }
+                    OwnKind::Typed {
+                        package: None,
+                        blueprint,
+                    } if self.extern_blueprints.contains(blueprint) => {
+                        format!("Owned<{}>", blueprint)
+                    }
+                    _ => "Own".to_string(),
+                }
+            }
+            SborEzModeSchemaKind::InternalAddress { .. } => "Own".to_string(),
+            SborEzModeSchemaKind::Integer { bits, signed } => {
+                format!("{}{}", if *signed { "i" } else { "u" }, bits)
+            }
+            SborEzModeSchemaKind::String => "String".to_string(),
+            SborEzModeSchemaKind::Bool => "bool".to_string(),
+            SborEzModeSchemaKind::NonFungibleLocalId => {
+                "NonFungibleLocalId".to_string()
+            }
+            SborEzModeSchemaKind::Value => "ScryptoValue".to_string(),
+            SborEzModeSchemaKind::Bytes => "Vec<u8>".to_string(),
+            SborEzModeSchemaKind::WellKnown(well_known) => {
+                well_known.name().to_string()
+            }
+            // Structs and enums are declared separately, and referred to by
+            // name.
+            SborEzModeSchemaKind::Struct { .. }
+            | SborEzModeSchemaKind::Enum { .. } => {
+                unreachable!("{:?} is not rendered inline", entry.schema_kind)
+            }
+        }
+    }
+
+    /// The fields of a struct or of an enum variant, in braces or
+    /// parentheses.
+    fn fields_doc(
+        &self,
+        registry: &SchemaRegistry,
+        kind: &SborEzModeSchemaKind,
+        visibility: &str,
+    ) -> Doc {
+        match kind {
+            SborEzModeSchemaKind::Struct { fields } => list(
+                "{",
+                fields
+                    .iter()
+                    .map(|(name, field_type)| {
+                        Doc::text(format!(
+                            "{}{}: {}",
+                            visibility,
+                            rust_identifier(name),
+                            self.type_of(registry, *field_type)
+                        ))
+                    })
+                    .collect(),
+                "}",
+                true,
+            ),
+            SborEzModeSchemaKind::Tuple { fields } => list(
+                "(",
+                fields
+                    .iter()
+                    .map(|&field| {
+                        Doc::text(format!(
+                            "{}{}",
+                            visibility,
+                            self.type_of(registry, field)
+                        ))
+                    })
+                    .collect(),
+                ")",
+                false,
+            ),
+            kind => unreachable!("{:?} has no fields", kind),
+        }
+    }
+
+    /// The declaration of a struct or enum.
+    fn declaration(
+        &self,
+        registry: &SchemaRegistry,
+        index: u32,
+        derives: &[&str],
+        options: &RenderOptions,
+    ) -> String {
+        let entry = &registry.entries[index as usize];
+        let name = rust_identifier(registry.var_name(index));
+        let indent = " ".repeat(options.pretty.indent_width);
+        let body = match &entry.schema_kind {
+            SborEzModeSchemaKind::Struct { fields } if fields.is_empty() => {
+                format!("pub struct {} {{}}", name)
+            }
+            SborEzModeSchemaKind::Struct { fields } => {
+                let fields: Vec<String> = fields
+                    .iter()
+                    .map(|(field, field_type)| {
+                        format!(
+                            "{}pub {}: {},\n",
+                            indent,
+                            rust_identifier(field),
+                            self.type_of(registry, *field_type)
+                        )
+                    })
+                    .collect();
+                format!("pub struct {} {{\n{}}}", name, fields.concat())
+            }
+            SborEzModeSchemaKind::Tuple { fields } if fields.is_empty() => {
+                format!("pub struct {};", name)
+            }
+            SborEzModeSchemaKind::Tuple { .. } => {
+                let declaration = Doc::concat(vec![
+                    Doc::text(format!("pub struct {}", name)),
+                    self.fields_doc(registry, &entry.schema_kind, "pub "),
+                    Doc::text(";"),
+                ]);
+                declaration.print(&options.pretty)
+            }
+            SborEzModeSchemaKind::Enum { variants } => {
+                // The derive numbers variants by their position, unless
+                // every variant is given its discriminator.
+                let explicit = variants
+                    .iter()
+                    .enumerate()
+                    .any(|(i, variant)| variant.discriminator as usize != i);
+                let variants: Vec<String> = variants
+                    .iter()
+                    .map(|variant| {
+                        let mut variant_doc =
+                            vec![Doc::text(rust_identifier(&variant.name))];
+                        if let Some(payload) = variant.payload {
+                            let kind =
+                                &registry.entries[payload as usize].schema_kind;
+                            if matches!(
+                                kind,
+                                SborEzModeSchemaKind::Struct { .. }
+                            ) {
+                                variant_doc.push(Doc::text(" "));
+                            }
+                            variant_doc
+                                .push(self.fields_doc(registry, kind, ""));
+                        }
+                        variant_doc.push(Doc::text(","));
+                        let attribute = if explicit {
+                            format!(
+                                "{}#[sbor(discriminator({}))]\n",
+                                indent, variant.discriminator
+                            )
+                        } else {
+                            String::new()
+                        };
+                        format!(
+                            "{}{}{}\n",
+                            attribute,
+                            indent,
+                            Doc::concat(variant_doc)
+                                .print_nested(1, &options.pretty)
+                        )
+                    })
+                    .collect();
+                format!("pub enum {} {{\n{}}}", name, variants.concat())
+            }
+            kind => unreachable!("{:?} is rendered inline", kind),
+        };
+        format!("#[derive({})]\n{}\n\n", derives.join(", "), body)
+    }
+
+    /// Declares every reachable named type as a struct or enum, and the
+    /// inline types among the roots as type aliases.
+    pub fn declarations(
+        &self,
+        registry: &SchemaRegistry,
+        reachable: &HashSet<u32>,
+        roots: &HashSet<u32>,
+        options: &RenderOptions,
+    ) -> String {
+        // Variant payloads are declared as part of their enum.
+        let payloads: HashSet<u32> = reachable
+            .iter()
+            .flat_map(|&i| match &registry.entries[i as usize].schema_kind {
+                SborEzModeSchemaKind::Enum { variants } => variants
+                    .iter()
+                    .filter_map(|variant| variant.payload)
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect();
+        let map_keys = self.map_keys(registry, reachable);
+        let sorted_indices = registry
+            .topologically_sorted_indices()
+            .unwrap_or_else(|err| {
+                panic!("Error performing topological sort: {}", err);
+            });
+
+        let mut output = String::new();
+        for i in sorted_indices {
+            let entry = &registry.entries[i as usize];
+            if !reachable.contains(&i) || payloads.contains(&i) {
+                continue;
+            }
+            if is_inline(entry) {
+                if roots.contains(&i) {
+                    output.push_str(&format!(
+                        "pub type {} = {};\n\n",
+                        rust_identifier(registry.var_name(i)),
+                        self.inline_type(registry, entry)
+                    ));
+                }
+                continue;
+            }
+            // Buckets and proofs can't be copied or printed.
+            let mut derives = vec!["ScryptoSbor"];
+            if !self.engine_types {
+                derives.extend(["Debug", "Clone"]);
+            }
+            if map_keys.contains(&i) {
+                derives.extend(["PartialEq", "Eq", "Hash"]);
+            }
+            output.push_str(&self.declaration(registry, i, &derives, options));
+        }
+        output
+    }
+
+    /// The entries that are used as the key of a map, which need to derive
+    /// `Hash` and `Eq`, together with everything they contain.
+    fn map_keys(
+        &self,
+        registry: &SchemaRegistry,
+        reachable: &HashSet<u32>,
+    ) -> HashSet<u32> {
+        let keys = reachable.iter().filter_map(|&i| {
+            match registry.entries[i as usize].schema_kind {
+                SborEzModeSchemaKind::Map { key_type, .. } => Some(key_type),
+                _ => None,
+            }
+        });
+        registry.reachable(keys)
+    }
+}
+
+impl Renderer for RustRenderer {
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let Selection {
+            blueprints,
+            types,
+            reachable,
+        } = registry.select(&options.filter);
+        let roots: HashSet<u32> = blueprints
+            .iter()
+            .flat_map(|roots| {
+                roots
+                    .state
+                    .into_iter()
+                    .chain(roots.events.iter().map(|(_, index)| *index))
+            })
+            .chain(types)
+            .collect();
+        let mut output = format!(
+            "// Generated Rust types for Scrypto SBOR types of package address: {}\n\nuse scrypto::prelude::*;\n\n{}",
+            package_address,
+            self.declarations(registry, &reachable, &roots, options)
+        );
+        output.truncate(output.trim_end().len());
+        output.push('\n');
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{testing::entry, BlueprintRoots, EnumVariant};
+    use crate::naming::RenameMap;
+    use crate::well_known::WellKnownType;
+
+    fn variant(
+        discriminator: u8,
+        name: &str,
+        payload: Option<u32>,
+    ) -> EnumVariant {
+        EnumVariant {
+            discriminator,
+            name: name.to_string(),
+            payload,
+        }
+    }
+
+    #[test]
+    fn test_identifiers_are_escaped() {
+        assert_eq!(rust_identifier("HashMap<K, V>"), "HashMap_K_V");
+        assert_eq!(rust_identifier("0th"), "_0th");
+        assert_eq!(rust_identifier("type"), "r#type");
+        assert_eq!(rust_identifier("self"), "self_");
+        assert_eq!(rust_identifier("my-field"), "my_field");
+    }
+
+    /// The output for a small pool package is checked in, and compiled by
+    /// the `rust_gen` integration test, which decodes Scrypto encoded values
+    /// with it. Run with `UPDATE_FIXTURES=1` to regenerate it.
+    #[test]
+    fn test_pool_renders_compiling_rust() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::Address {
+                    reference: ReferenceKind::Resource,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 64,
+                    signed: false,
+                },
+            ),
+            entry(None, SborEzModeSchemaKind::String),
+            entry(
+                Some("SwapEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("input_amount".to_string(), 0),
+                        ("input_resource".to_string(), 1),
+                        ("output_amount".to_string(), 0),
+                        ("type".to_string(), 3),
+                    ],
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Map {
+                    key_type: 1,
+                    value_type: 0,
+                },
+            ),
+            entry(None, SborEzModeSchemaKind::Tuple { fields: vec![0] }),
+            entry(None, SborEzModeSchemaKind::Array { element_type: 0 }),
+            entry(
+                None,
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("steps".to_string(), 7)],
+                },
+            ),
+            entry(
+                Some("FeeTier"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![
+                        variant(0, "Low", None),
+                        variant(1, "Custom", Some(6)),
+                        variant(3, "Stepped", Some(8)),
+                    ],
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::WellKnown(
+                    WellKnownType::NonFungibleGlobalId,
+                ),
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::WellKnown(WellKnownType::Instant),
+            ),
+            entry(Some("Option"), SborEzModeSchemaKind::Option { some: 11 }),
+            entry(
+                Some("PoolState"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("reserves".to_string(), 5),
+                        ("fee_tier".to_string(), 9),
+                        ("admin_badge".to_string(), 10),
+                        ("last_swap".to_string(), 12),
+                        ("swap_count".to_string(), 2),
+                        ("prices".to_string(), 16),
+                    ],
+                },
+            ),
+            entry(
+                Some("PriceUpdate"),
+                SborEzModeSchemaKind::Tuple { fields: vec![3, 2] },
+            ),
+            entry(
+                Some("PairKey"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("base".to_string(), 1),
+                        ("quote".to_string(), 1),
+                    ],
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Map {
+                    key_type: 15,
+                    value_type: 0,
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(13),
+            events: vec![
+                ("SwapEvent".to_string(), 4),
+                ("PriceUpdate".to_string(), 14),
+            ],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output = RustRenderer::default().render(
+            &registry,
+            "package_pool",
+            &Default::default(),
+        );
+
+        let fixture = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/tests/fixtures/pool.generated.rs"
+        );
+        if std::env::var_os("UPDATE_FIXTURES").is_some() {
+            std::fs::write(fixture, &output).unwrap();
+        }
+        assert_eq!(output, std::fs::read_to_string(fixture).unwrap());
+    }
+}
@@ -0,0 +1,687 @@
+//! Rendering of the registry as Postgres tables for indexing the events and
+//! state of a package. Every event gets a table, and so does the state of
+//! every blueprint unless the filter leaves it out. Scalar fields become
+//! columns, and fields of nested structs and tuples are flattened into
+//! columns prefixed with the field name. Enums, arrays, maps and other
+//! values that don't fit a column are stored as JSONB, in the shape the
+//! sbor-ez-mode parsers return them.
+
+use crate::ez_mode_gen::{SborEzModeSchemaKind, SchemaRegistry, Selection};
+use crate::renderer::{RenderOptions, Renderer};
+use crate::well_known::WellKnownType;
+use std::collections::HashSet;
+
+/// Keywords that Postgres reserves, which must be quoted as column names.
+const RESERVED: &[&str] = &[
+    "all",
+    "analyse",
+    "analyze",
+    "and",
+    "any",
+    "array",
+    "as",
+    "asc",
+    "asymmetric",
+    "authorization",
+    "binary",
+    "both",
+    "case",
+    "cast",
+    "check",
+    "collate",
+    "collation",
+    "column",
+    "concurrently",
+    "constraint",
+    "create",
+    "cross",
+    "current_catalog",
+    "current_date",
+    "current_role",
+    "current_schema",
+    "current_time",
+    "current_timestamp",
+    "current_user",
+    "default",
+    "deferrable",
+    "desc",
+    "distinct",
+    "do",
+    "else",
+    "end",
+    "except",
+    "false",
+    "fetch",
+    "for",
+    "foreign",
+    "freeze",
+    "from",
+    "full",
+    "grant",
+    "group",
+    "having",
+    "ilike",
+    "in",
+    "initially",
+    "inner",
+    "intersect",
+    "into",
+    "is",
+    "isnull",
+    "join",
+    "lateral",
+    "leading",
+    "left",
+    "like",
+    "limit",
+    "localtime",
+    "localtimestamp",
+    "natural",
+    "not",
+    "notnull",
+    "null",
+    "offset",
+    "on",
+    "only",
+    "or",
+    "order",
+    "outer",
+    "overlaps",
+    "placing",
+    "primary",
+    "references",
+    "returning",
+    "right",
+    "select",
+    "session_user",
+    "similar",
+    "some",
+    "symmetric",
+    "system_user",
+    "table",
+    "tablesample",
+    "then",
+    "to",
+    "trailing",
+    "true",
+    "union",
+    "unique",
+    "user",
+    "using",
+    "variadic",
+    "verbose",
+    "when",
+    "where",
+    "window",
+    "with",
+];
+
+/// Postgres truncates identifiers to this many bytes.
+const MAX_IDENTIFIER: usize = 63;
+
+/// Cuts an identifier down to at most `max` bytes, as Postgres would.
+fn truncate(identifier: &str, max: usize) -> &str {
+    let mut end = identifier.len().min(max);
+    while !identifier.is_char_boundary(end) {
+        end -= 1;
+    }
+    &identifier[..end]
+}
+
+/// Truncates a name, and gives it a suffix of `_`s while it is taken. The
+/// suffix replaces the end of long names, so that the result still fits.
+fn unique(base: &str, taken: impl Fn(&str) -> bool) -> String {
+    let mut name = truncate(base, MAX_IDENTIFIER).to_string();
+    let mut suffix = 0;
+    while taken(&name) {
+        suffix += 1;
+        name = format!(
+            "{}{}",
+            truncate(base, MAX_IDENTIFIER - suffix),
+            "_".repeat(suffix)
+        );
+    }
+    name
+}
+
+/// Turns a name into a snake case identifier, such as `swap_event` for
+/// `SwapEvent`.
+pub(crate) fn snake_case(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut result = String::with_capacity(name.len());
+    for (i, &c) in chars.iter().enumerate() {
+        if !c.is_ascii_alphanumeric() {
+            if !result.is_empty() && !result.ends_with('_') {
+                result.push('_');
+            }
+            continue;
+        }
+        // Start a word at an uppercase letter after a lowercase letter or
+        // digit, and at the last letter of an acronym, as in `HTTPServer`.
+        let previous = i.checked_sub(1).map(|i| chars[i]);
+        let next = chars.get(i + 1);
+        let starts_word = c.is_ascii_uppercase()
+            && previous.is_some_and(|previous| {
+                previous.is_ascii_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_ascii_uppercase()
+                        && next.is_some_and(|next| next.is_ascii_lowercase()))
+            });
+        if starts_word && !result.is_empty() && !result.ends_with('_') {
+            result.push('_');
+        }
+        result.push(c.to_ascii_lowercase());
+    }
+    result.trim_end_matches('_').to_string()
+}
+
+/// Quotes an identifier when it is a reserved keyword, and prefixes the
+/// positional fields of tuples, which start with a digit.
+fn quote(identifier: &str) -> String {
+    if RESERVED.contains(&identifier) {
+        format!("\"{}\"", identifier)
+    } else if identifier.starts_with(|c: char| c.is_ascii_digit()) {
+        format!("field_{}", identifier)
+    } else {
+        identifier.to_string()
+    }
+}
+
+/// A column of an indexing table.
+struct Column {
+    name: String,
+    sql_type: String,
+    nullable: bool,
+}
+
+impl Column {
+    fn new(name: &str, sql_type: &str, nullable: bool) -> Self {
+        Self {
+            name: name.to_string(),
+            sql_type: sql_type.to_string(),
+            nullable,
+        }
+    }
+}
+
+/// Renders the registry as `CREATE TABLE` statements for Postgres.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SqlRenderer;
+
+impl SqlRenderer {
+    /// The column type of an entry that fits in a single column, or `None`
+    /// for entries that are stored as JSONB.
+    fn scalar_type(
+        &self,
+        registry: &SchemaRegistry,
+        index: u32,
+    ) -> Option<String> {
+        let kind = &registry.entries[index as usize].schema_kind;
+        if let Some(precision) = kind.decimal_precision() {
+            return Some(format!(
+                "NUMERIC({}, {})",
+                precision.total_digits, precision.decimal_places
+            ));
+        }
+        let sql_type = match kind {
+            SborEzModeSchemaKind::Address { .. }
+            | SborEzModeSchemaKind::InternalAddress { .. }
+            | SborEzModeSchemaKind::String
+            | SborEzModeSchemaKind::NonFungibleLocalId => "TEXT",
+            SborEzModeSchemaKind::Bool => "BOOLEAN",
+            SborEzModeSchemaKind::Bytes => "BYTEA",
+            SborEzModeSchemaKind::Integer { bits, signed } => {
+                match (bits, signed) {
+                    (8, _) | (16, true) => "SMALLINT",
+                    (16, false) | (32, true) => "INTEGER",
+                    (32, false) | (64, true) => "BIGINT",
+                    (64, false) => "NUMERIC(20, 0)",
+                    _ => "NUMERIC(39, 0)",
+                }
+            }
+            SborEzModeSchemaKind::WellKnown(well_known) => match well_known {
+                WellKnownType::Instant | WellKnownType::UtcDateTime => {
+                    "TIMESTAMPTZ"
+                }
+                // In its `resource_address:local_id` form.
+                WellKnownType::NonFungibleGlobalId => "TEXT",
+                WellKnownType::PublicKey | WellKnownType::PublicKeyHash => {
+                    return None
+                }
+                _ => "BYTEA",
+            },
+            _ => return None,
+        };
+        Some(sql_type.to_string())
+    }
+
+    /// Appends the columns of an entry, flattening structs and tuples into
+    /// a column per field.
+    fn columns(
+        &self,
+        registry: &SchemaRegistry,
+        index: u32,
+        name: &str,
+        nullable: bool,
+        options: &RenderOptions,
+        columns: &mut Vec<Column>,
+    ) {
+        let entry = &registry.entries[index as usize];
+        // A root that isn't flattened is stored in a `value` column.
+        let column_name = if name.is_empty() { "value" } else { name };
+        match &entry.schema_kind {
+            SborEzModeSchemaKind::Struct { .. }
+            | SborEzModeSchemaKind::Tuple { .. } => {
+                let nullable = nullable || registry.is_lenient(entry, options);
+                for (field, child) in entry.schema_kind.children() {
+                    let field = snake_case(&field);
+                    self.columns(
+                        registry,
+                        child,
+                        &if name.is_empty() {
+                            field
+                        } else {
+                            format!("{}_{}", name, field)
+                        },
+                        nullable,
+                        options,
+                        columns,
+                    );
+                }
+            }
+            SborEzModeSchemaKind::Option { some } => {
+                let sql_type = self
+                    .scalar_type(registry, *some)
+                    .unwrap_or_else(|| "JSONB".to_string());
+                columns.push(Column::new(column_name, &sql_type, true));
+            }
+            _ => {
+                let sql_type = self
+                    .scalar_type(registry, index)
+                    .unwrap_or_else(|| "JSONB".to_string());
+                columns.push(Column::new(column_name, &sql_type, nullable));
+            }
+        }
+    }
+
+    /// Declares a table with the standard columns, followed by the columns
+    /// of the root entry.
+    fn table(
+        &self,
+        registry: &SchemaRegistry,
+        name: &str,
+        standard_columns: Vec<Column>,
+        primary_key: &[&str],
+        root: u32,
+        options: &RenderOptions,
+    ) -> String {
+        let mut columns = standard_columns;
+        let standard = columns.len();
+        self.columns(registry, root, "", false, options, &mut columns);
+        // Fields that clash with the standard columns, or with each other
+        // after flattening and truncating, get a suffix.
+        for i in standard..columns.len() {
+            let mut base = columns[i].name.clone();
+            if base.starts_with(|c: char| c.is_ascii_digit()) {
+                base = format!("field_{}", base);
+            }
+            let name = unique(&base, |name| {
+                columns[..i].iter().any(|c| c.name == name)
+            });
+            columns[i].name = name;
+        }
+
+        let indent = " ".repeat(options.pretty.indent_width);
+        let mut lines: Vec<String> = columns
+            .iter()
+            .map(|column| {
+                format!(
+                    "{}{} {}{}",
+                    indent,
+                    quote(&column.name),
+                    column.sql_type,
+                    if column.nullable { "" } else { " NOT NULL" }
+                )
+            })
+            .collect();
+        lines.push(format!(
+            "{}PRIMARY KEY ({})",
+            indent,
+            primary_key.join(", ")
+        ));
+        format!(
+            "CREATE TABLE IF NOT EXISTS {} (\n{}\n);\n",
+            quote(name),
+            lines.join(",\n")
+        )
+    }
+}
+
+impl Renderer for SqlRenderer {
+    fn render(
+        &self,
+        registry: &SchemaRegistry,
+        package_address: &str,
+        options: &RenderOptions,
+    ) -> String {
+        let Selection { blueprints, .. } = registry.select(&options.filter);
+        let mut tables = Vec::new();
+        // Tables and indexes share a namespace. Names that clash after
+        // truncating, or an event named `State`, get a suffix like columns.
+        let mut taken: HashSet<String> = HashSet::new();
+        let mut unique_name = |base: &str| {
+            let name = unique(base, |name| taken.contains(name));
+            taken.insert(name.clone());
+            name
+        };
+        for roots in &blueprints {
+            let blueprint = snake_case(&roots.blueprint);
+            if let Some(state) = roots.state {
+                let name = unique_name(&format!("{}_state", blueprint));
+                tables.push(format!(
+                    "-- The state of the {} blueprint, by the state version \
+                     that changed it.\n{}",
+                    roots.blueprint,
+                    self.table(
+                        registry,
+                        &name,
+                        vec![
+                            Column::new("component_address", "TEXT", false),
+                            Column::new("state_version", "BIGINT", false),
+                            Column::new("transaction_hash", "TEXT", false),
+                        ],
+                        &["component_address", "state_version"],
+                        state,
+                        options,
+                    )
+                ));
+            }
+            for (event, index) in &roots.events {
+                let name = unique_name(&format!(
+                    "{}_{}",
+                    blueprint,
+                    snake_case(event)
+                ));
+                let emitter_index = unique_name(&format!(
+                    "{}_emitter",
+                    truncate(&name, MAX_IDENTIFIER - "_emitter".len())
+                ));
+                tables.push(format!(
+                    "-- The {} event of the {} blueprint.\n{}\n\
+                     CREATE INDEX IF NOT EXISTS {} ON {} (emitter);\n",
+                    event,
+                    roots.blueprint,
+                    self.table(
+                        registry,
+                        &name,
+                        vec![
+                            Column::new("state_version", "BIGINT", false),
+                            Column::new("event_index", "INTEGER", false),
+                            Column::new("transaction_hash", "TEXT", false),
+                            Column::new("emitter", "TEXT", false),
+                        ],
+                        &["state_version", "event_index"],
+                        *index,
+                        options,
+                    ),
+                    emitter_index,
+                    quote(&name)
+                ));
+            }
+        }
+        format!(
+            "-- Generated Postgres tables for indexing package address: {}\n\
+             -- JSONB columns hold the values as parsed by sbor-ez-mode.\n\n{}",
+            package_address,
+            tables.join("\n")
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ez_mode_gen::{
+        testing::entry, BlueprintRoots, EnumVariant, ReferenceKind,
+    };
+    use crate::naming::RenameMap;
+
+    #[test]
+    fn test_names_are_snake_case() {
+        assert_eq!(snake_case("SwapEvent"), "swap_event");
+        assert_eq!(snake_case("HTTPServer"), "http_server");
+        assert_eq!(snake_case("HashMap<K, V>"), "hash_map_k_v");
+        assert_eq!(snake_case("pool_v2"), "pool_v2");
+        assert_eq!(quote("order"), "\"order\"");
+        assert_eq!(quote("0"), "field_0");
+    }
+
+    #[test]
+    fn test_events_are_flattened_into_columns() {
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::Address {
+                    reference: ReferenceKind::Resource,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 64,
+                    signed: false,
+                },
+            ),
+            entry(
+                Some("Side"),
+                SborEzModeSchemaKind::Enum {
+                    variants: vec![EnumVariant {
+                        discriminator: 0,
+                        name: "Buy".to_string(),
+                        payload: None,
+                    }],
+                },
+            ),
+            entry(Some("Option"), SborEzModeSchemaKind::Option { some: 0 }),
+            entry(None, SborEzModeSchemaKind::Tuple { fields: vec![1, 0] }),
+            entry(
+                Some("SwapEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        ("input".to_string(), 5),
+                        ("side".to_string(), 3),
+                        ("limit".to_string(), 4),
+                        ("emitter".to_string(), 1),
+                        ("order".to_string(), 2),
+                        ("decimals".to_string(), 8),
+                        ("fee_bps".to_string(), 9),
+                        ("tick".to_string(), 10),
+                    ],
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Map {
+                    key_type: 1,
+                    value_type: 0,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 8,
+                    signed: false,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 16,
+                    signed: false,
+                },
+            ),
+            entry(
+                None,
+                SborEzModeSchemaKind::Integer {
+                    bits: 16,
+                    signed: true,
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "LiquidityPool".to_string(),
+            state: Some(7),
+            events: vec![("SwapEvent".to_string(), 6)],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output =
+            SqlRenderer.render(&registry, "package_sql", &Default::default());
+
+        assert_eq!(
+            output,
+            r#"-- Generated Postgres tables for indexing package address: package_sql
+-- JSONB columns hold the values as parsed by sbor-ez-mode.
+
+-- The state of the LiquidityPool blueprint, by the state version that changed it.
+CREATE TABLE IF NOT EXISTS liquidity_pool_state (
+    component_address TEXT NOT NULL,
+    state_version BIGINT NOT NULL,
+    transaction_hash TEXT NOT NULL,
+    value JSONB NOT NULL,
+    PRIMARY KEY (component_address, state_version)
+);
+
+-- The SwapEvent event of the LiquidityPool blueprint.
+CREATE TABLE IF NOT EXISTS liquidity_pool_swap_event (
+    state_version BIGINT NOT NULL,
+    event_index INTEGER NOT NULL,
+    transaction_hash TEXT NOT NULL,
+    emitter TEXT NOT NULL,
+    input_0 TEXT NOT NULL,
+    input_1 NUMERIC(58, 18) NOT NULL,
+    side JSONB NOT NULL,
+    "limit" NUMERIC(58, 18),
+    emitter_ TEXT NOT NULL,
+    "order" NUMERIC(20, 0) NOT NULL,
+    decimals SMALLINT NOT NULL,
+    fee_bps INTEGER NOT NULL,
+    tick SMALLINT NOT NULL,
+    PRIMARY KEY (state_version, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS liquidity_pool_swap_event_emitter ON liquidity_pool_swap_event (emitter);
+"#
+        );
+    }
+
+    #[test]
+    fn test_long_names_are_truncated_before_deduplication() {
+        let long =
+            "amount_in_the_base_currency_of_the_liquidity_pool_before_fees";
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                None,
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![
+                        (format!("{}_in", long), 0),
+                        (format!("{}_out", long), 0),
+                    ],
+                },
+            ),
+            entry(
+                Some("SwapEventOfTheConcentratedLiquidityPoolWithTicks"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("swapped".to_string(), 1)],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "LiquidityPool".to_string(),
+            state: None,
+            events: vec![(
+                "SwapEventOfTheConcentratedLiquidityPoolWithTicks".to_string(),
+                2,
+            )],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output =
+            SqlRenderer.render(&registry, "package_sql", &Default::default());
+
+        assert_eq!(
+            output,
+            r#"-- Generated Postgres tables for indexing package address: package_sql
+-- JSONB columns hold the values as parsed by sbor-ez-mode.
+
+-- The SwapEventOfTheConcentratedLiquidityPoolWithTicks event of the LiquidityPool blueprint.
+CREATE TABLE IF NOT EXISTS liquidity_pool_swap_event_of_the_concentrated_liquidity_pool_wi (
+    state_version BIGINT NOT NULL,
+    event_index INTEGER NOT NULL,
+    transaction_hash TEXT NOT NULL,
+    emitter TEXT NOT NULL,
+    swapped_amount_in_the_base_currency_of_the_liquidity_pool_befor NUMERIC(58, 18) NOT NULL,
+    swapped_amount_in_the_base_currency_of_the_liquidity_pool_befo_ NUMERIC(58, 18) NOT NULL,
+    PRIMARY KEY (state_version, event_index)
+);
+
+CREATE INDEX IF NOT EXISTS liquidity_pool_swap_event_of_the_concentrated_liquidity_emitter ON liquidity_pool_swap_event_of_the_concentrated_liquidity_pool_wi (emitter);
+"#
+        );
+    }
+
+    #[test]
+    fn test_table_names_are_deduplicated() {
+        let long = "SwapEventOfTheConcentratedLiquidityPoolWithTicksAnd";
+        let mut registry = SchemaRegistry::new();
+        registry.entries = vec![
+            entry(None, SborEzModeSchemaKind::Decimal),
+            entry(
+                Some("PoolState"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("fee".to_string(), 0)],
+                },
+            ),
+            entry(
+                Some("StateEvent"),
+                SborEzModeSchemaKind::Struct {
+                    fields: vec![("fee".to_string(), 0)],
+                },
+            ),
+        ];
+        registry.roots.push(BlueprintRoots {
+            blueprint: "Pool".to_string(),
+            state: Some(1),
+            events: vec![
+                ("State".to_string(), 2),
+                (format!("{}Fees", long), 2),
+                (format!("{}Rebates", long), 2),
+            ],
+            functions: Vec::new(),
+        });
+        registry.assign_names(&RenameMap::new());
+        let output =
+            SqlRenderer.render(&registry, "package_sql", &Default::default());
+
+        let statements: Vec<&str> = output
+            .lines()
+            .filter(|line| line.starts_with("CREATE"))
+            .collect();
+        assert_eq!(
+            statements,
+            [
+                "CREATE TABLE IF NOT EXISTS pool_state (",
+                "CREATE TABLE IF NOT EXISTS pool_state_ (",
+                "CREATE INDEX IF NOT EXISTS pool_state__emitter ON pool_state_ (emitter);",
+                "CREATE TABLE IF NOT EXISTS pool_swap_event_of_the_concentrated_liquidity_pool_with_ticks_a (",
+                "CREATE INDEX IF NOT EXISTS pool_swap_event_of_the_concentrated_liquidity_pool_with_emitter ON pool_swap_event_of_the_concentrated_liquidity_pool_with_ticks_a (emitter);",
+                "CREATE TABLE IF NOT EXISTS pool_swap_event_of_the_concentrated_liquidity_pool_with_ticks__ (",
+                "CREATE INDEX IF NOT EXISTS pool_swap_event_of_the_concentrated_liquidity_pool_with_emitte_ ON pool_swap_event_of_the_concentrated_liquidity_pool_with_ticks__ (emitter);",
+            ]
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Escaping of names from SBOR schemas for use in generated TypeScript.
+//! Type, field and variant names come straight from the package metadata, so
+//! they can contain anything: generics punctuation (`HashMap<K, V>`), quotes,
+//! or words that are reserved in TypeScript.
+
+use crate::identifier::sanitize;
+
+/// Words that can't be used as variable names in a TypeScript module, and
+/// the predefined types, which can't be used as type names. Generated names
+/// are used for types as well, see `--with-types`.
+pub const RESERVED_WORDS: &[&str] = &[
+    "any",
+    "arguments",
+    "await",
+    "bigint",
+    "boolean",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "eval",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "implements",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "never",
+    "new",
+    "null",
+    "number",
+    "object",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "string",
+    "super",
+    "switch",
+    "symbol",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "undefined",
+    "unknown",
+    "var",
+    "void",
+    "while",
+    "with",
+    "yield",
+];
+
+fn is_identifier_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_' || c == '$'
+}
+
+fn is_identifier_part(c: char) -> bool {
+    crate::identifier::is_identifier_part(c) || c == '$'
+}
+
+/// Whether a name can be used as is for an object key.
+pub fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    match chars.next() {
+        Some(c) => is_identifier_start(c) && chars.all(is_identifier_part),
+        None => false,
+    }
+}
+
+/// Turns a name into a valid variable name, see [`sanitize`]. Unlike the
+/// other languages, `$` is kept.
+pub fn identifier(name: &str) -> String {
+    sanitize(name, is_identifier_part, RESERVED_WORDS)
+}
+
+/// A double quoted string literal.
+pub fn string_literal(value: &str) -> String {
+    let mut result = String::with_capacity(value.len() + 2);
+    result.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => result.push_str("\\\""),
+            '\\' => result.push_str("\\\\"),
+            '\n' => result.push_str("\\n"),
+            '\r' => result.push_str("\\r"),
+            '\t' => result.push_str("\\t"),
+            c if c.is_control() || c == '\u{2028}' || c == '\u{2029}' => {
+                result.push_str(&format!("\\u{{{:x}}}", c as u32))
+            }
+            c => result.push(c),
+        }
+    }
+    result.push('"');
+    result
+}
+
+/// An object key, which is quoted when it isn't an identifier.
+pub fn property_key(name: &str) -> String {
+    if is_identifier(name) {
+        name.to_string()
+    } else {
+        string_literal(name)
+    }
+}
+
+/// Text that can be placed inside a `/* */` comment.
+pub fn comment(text: &str) -> String {
+    text.replace("*/", "*\\/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_identifiers_are_sanitized() {
+        assert_eq!(identifier("Pool"), "Pool");
+        assert_eq!(identifier("HashMap<K, V>"), "HashMap_K_V");
+        assert_eq!(identifier("Vec<u8>"), "Vec_u8");
+        assert_eq!(identifier("class"), "class_");
+        assert_eq!(identifier("string"), "string_");
+        assert_eq!(identifier("undefined"), "undefined_");
+        assert_eq!(identifier("0th"), "_0th");
+        assert_eq!(identifier("<>"), "_");
+        assert_eq!(identifier("Pool_State_0"), "Pool_State_0");
+        assert_eq!(identifier("$scope"), "$scope");
+        assert_eq!(identifier("Vec<$T>"), "Vec_$T");
+    }
+
+    #[test]
+    fn test_keys_and_strings_are_escaped() {
+        assert_eq!(property_key("class"), "class");
+        assert_eq!(property_key("my-field"), "\"my-field\"");
+        assert_eq!(string_literal("say \"hi\"\n"), "\"say \\\"hi\\\"\\n\"");
+        assert_eq!(comment("a */ b"), "a *\\/ b");
+    }
+}
@@ -0,0 +1,235 @@
+//! Recognition of well known Scrypto types.
+//! Most types are mapped structurally, but some have a meaning beyond their
+//! shape, such as an `Instant` being an `i64`, or a `NonFungibleGlobalId`
+//! being a tuple of a resource address and a local id. This module holds the
+//! central table of those types, matched either by their well known type id,
+//! or by their name together with their shape. Matching on the shape as well
+//! means that a user type which happens to be called `Option` isn't mistaken
+//! for the real thing.
+
+use radix_common::data::scrypto::well_known_scrypto_custom_types::{
+    ED25519_PUBLIC_KEY_HASH_TYPE, ED25519_PUBLIC_KEY_TYPE, INSTANT_TYPE,
+    PUBLIC_KEY_HASH_TYPE, PUBLIC_KEY_TYPE, SECP256K1_PUBLIC_KEY_HASH_TYPE,
+    SECP256K1_PUBLIC_KEY_TYPE, UTC_DATE_TIME_TYPE,
+};
+use sbor::prelude::indexmap::IndexMap;
+use sbor::{LocalTypeId, TypeKind, TypeMetadata, WellKnownTypeId};
+use scrypto::prelude::ScryptoCustomTypeKind;
+
+/// Well known types which don't refer to other types, and get a dedicated
+/// helper in the generated output.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize,
+)]
+pub enum WellKnownType {
+    Instant,
+    UtcDateTime,
+    NonFungibleGlobalId,
+    Hash,
+    PublicKey,
+    Secp256k1PublicKey,
+    Ed25519PublicKey,
+    PublicKeyHash,
+    Secp256k1PublicKeyHash,
+    Ed25519PublicKeyHash,
+}
+
+impl WellKnownType {
+    /// The name of the type in Scrypto.
+    pub fn name(&self) -> &'static str {
+        match self {
+            WellKnownType::Instant => "Instant",
+            WellKnownType::UtcDateTime => "UtcDateTime",
+            WellKnownType::NonFungibleGlobalId => "NonFungibleGlobalId",
+            WellKnownType::Hash => "Hash",
+            WellKnownType::PublicKey => "PublicKey",
+            WellKnownType::Secp256k1PublicKey => "Secp256k1PublicKey",
+            WellKnownType::Ed25519PublicKey => "Ed25519PublicKey",
+            WellKnownType::PublicKeyHash => "PublicKeyHash",
+            WellKnownType::Secp256k1PublicKeyHash => "Secp256k1PublicKeyHash",
+            WellKnownType::Ed25519PublicKeyHash => "Ed25519PublicKeyHash",
+        }
+    }
+}
+
+/// Well known types, by their well known type id.
+const WELL_KNOWN_TYPE_IDS: &[(WellKnownTypeId, WellKnownType)] = &[
+    (INSTANT_TYPE, WellKnownType::Instant),
+    (UTC_DATE_TIME_TYPE, WellKnownType::UtcDateTime),
+    (PUBLIC_KEY_TYPE, WellKnownType::PublicKey),
+    (SECP256K1_PUBLIC_KEY_TYPE, WellKnownType::Secp256k1PublicKey),
+    (ED25519_PUBLIC_KEY_TYPE, WellKnownType::Ed25519PublicKey),
+    (PUBLIC_KEY_HASH_TYPE, WellKnownType::PublicKeyHash),
+    (
+        SECP256K1_PUBLIC_KEY_HASH_TYPE,
+        WellKnownType::Secp256k1PublicKeyHash,
+    ),
+    (
+        ED25519_PUBLIC_KEY_HASH_TYPE,
+        WellKnownType::Ed25519PublicKeyHash,
+    ),
+];
+
+/// The shape a named type must have to be recognized.
+enum Shape {
+    /// A tuple with this many fields.
+    Tuple(usize),
+    /// An array, such as the `[u8; 32]` of a hash.
+    Array,
+}
+
+/// Well known types without a well known type id, by their name and shape.
+const NAMED_TYPES: &[(&str, Shape, WellKnownType)] = &[
+    (
+        "NonFungibleGlobalId",
+        Shape::Tuple(2),
+        WellKnownType::NonFungibleGlobalId,
+    ),
+    ("UtcDateTime", Shape::Tuple(6), WellKnownType::UtcDateTime),
+    ("Hash", Shape::Array, WellKnownType::Hash),
+];
+
+/// The result of looking a type up in the table.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Recognized {
+    WellKnown(WellKnownType),
+    /// A Rust `Option<T>`, holding the type of the `Some` value.
+    Option {
+        some: LocalTypeId,
+    },
+    /// A Rust `Result<T, E>`, holding the types of the `Ok` and `Err` values.
+    Result {
+        ok: LocalTypeId,
+        err: LocalTypeId,
+    },
+}
+
+/// Looks a type up in the table of well known types.
+pub fn recognize(
+    metadata: &TypeMetadata,
+    kind: &TypeKind<ScryptoCustomTypeKind, LocalTypeId>,
+    type_id: LocalTypeId,
+) -> Option<Recognized> {
+    if let LocalTypeId::WellKnown(id) = type_id {
+        if let Some((_, well_known)) =
+            WELL_KNOWN_TYPE_IDS.iter().find(|(known, _)| *known == id)
+        {
+            return Some(Recognized::WellKnown(*well_known));
+        }
+    }
+
+    let name = metadata.get_name()?;
+    if let TypeKind::Enum { variants } = kind {
+        return match name {
+            "Option" => {
+                let expected = [("None", 0), ("Some", 1)];
+                let [_, some] = enum_shape(metadata, variants, expected)?;
+                Some(Recognized::Option { some: some[0] })
+            }
+            "Result" => {
+                let expected = [("Ok", 1), ("Err", 1)];
+                let [ok, err] = enum_shape(metadata, variants, expected)?;
+                Some(Recognized::Result {
+                    ok: ok[0],
+                    err: err[0],
+                })
+            }
+            _ => None,
+        };
+    }
+
+    NAMED_TYPES
+        .iter()
+        .find(|(known_name, shape, _)| {
+            *known_name == name
+                && match (shape, kind) {
+                    (Shape::Tuple(len), TypeKind::Tuple { field_types }) => {
+                        field_types.len() == *len
+                    }
+                    (Shape::Array, TypeKind::Array { .. }) => true,
+                    _ => false,
+                }
+        })
+        .map(|(_, _, well_known)| Recognized::WellKnown(*well_known))
+}
+
+/// Checks that an enum has exactly the given variants, with discriminators
+/// 0, 1, ..., the given names and the given number of fields, and returns
+/// the field types of each variant.
+fn enum_shape<'a, const N: usize>(
+    metadata: &TypeMetadata,
+    variants: &'a IndexMap<u8, Vec<LocalTypeId>>,
+    expected: [(&str, usize); N],
+) -> Option<[&'a [LocalTypeId]; N]> {
+    if variants.len() != N {
+        return None;
+    }
+    let mut fields: [&[LocalTypeId]; N] = [&[]; N];
+    for (i, (name, field_count)) in expected.into_iter().enumerate() {
+        let field_types = variants.get(&(i as u8))?;
+        let variant_data =
+            metadata.get_matching_enum_variant_data(i as u8, field_types.len());
+        if field_types.len() != field_count
+            || variant_data.variant_name.as_deref() != Some(name)
+        {
+            return None;
+        }
+        fields[i] = field_types;
+    }
+    Some(fields)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sbor::prelude::indexmap;
+
+    fn option_metadata(name: &'static str) -> TypeMetadata {
+        TypeMetadata::enum_variants(
+            name,
+            indexmap![
+                0 => TypeMetadata::no_child_names("None"),
+                1 => TypeMetadata::no_child_names("Some"),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_option_is_recognized_by_shape() {
+        let some = LocalTypeId::SchemaLocalIndex(1);
+        let kind = TypeKind::Enum {
+            variants: indexmap![0 => vec![], 1 => vec![some]],
+        };
+        let type_id = LocalTypeId::SchemaLocalIndex(0);
+        assert_eq!(
+            recognize(&option_metadata("Option"), &kind, type_id),
+            Some(Recognized::Option { some })
+        );
+        assert_eq!(recognize(&option_metadata("Maybe"), &kind, type_id), None);
+    }
+
+    #[test]
+    fn test_user_types_named_option_are_not_recognized() {
+        let type_id = LocalTypeId::SchemaLocalIndex(0);
+        let field = LocalTypeId::SchemaLocalIndex(1);
+        let user_struct = TypeKind::Tuple {
+            field_types: vec![field],
+        };
+        assert_eq!(
+            recognize(
+                &TypeMetadata::no_child_names("Option"),
+                &user_struct,
+                type_id
+            ),
+            None
+        );
+        // An enum with an extra variant isn't an `Option` either.
+        let user_enum = TypeKind::Enum {
+            variants: indexmap![0 => vec![], 1 => vec![field], 2 => vec![]],
+        };
+        assert_eq!(
+            recognize(&option_metadata("Option"), &user_enum, type_id),
+            None
+        );
+    }
+}
@@ -0,0 +1,276 @@
+# Generated Python types for Scrypto SBOR types of package address: package_pool
+# Parse the programmatic JSON of the Gateway API with parse_event, or with
+# the parse_ function of a type.
+
+from __future__ import annotations
+
+from dataclasses import dataclass, field
+from datetime import datetime, timezone
+from decimal import Decimal
+from typing import Any, Callable, Generic, Literal, Optional, TypeVar, Union
+
+_T = TypeVar("_T")
+_E = TypeVar("_E")
+_Json = dict[str, Any]
+_Parser = Callable[[_Json], Any]
+
+_INTEGER_KINDS = ("I8", "I16", "I32", "I64", "I128", "U8", "U16", "U32", "U64", "U128")
+
+
+@dataclass(frozen=True)
+class Ok(Generic[_T]):
+    value: _T
+    variant: Literal["Ok"] = field(default="Ok", init=False)
+
+
+@dataclass(frozen=True)
+class Err(Generic[_E]):
+    value: _E
+    variant: Literal["Err"] = field(default="Err", init=False)
+
+
+@dataclass(frozen=True)
+class UnknownVariant:
+    """A variant of a lenient enum that isn't in the schema, by its name or id."""
+
+    variant: str
+    value: _Json
+
+
+def _expect(value: _Json, *kinds: str) -> _Json:
+    if value.get("kind") not in kinds:
+        raise ValueError(f"Expected {' or '.join(kinds)}, got {value.get('kind')}")
+    return value
+
+
+def _string(value: _Json) -> str:
+    return _expect(value, "String")["value"]
+
+
+def _bool(value: _Json) -> bool:
+    return _expect(value, "Bool")["value"]
+
+
+def _int(value: _Json) -> int:
+    return int(_expect(value, *_INTEGER_KINDS)["value"])
+
+
+def _decimal(value: _Json) -> Decimal:
+    return Decimal(_expect(value, "Decimal", "PreciseDecimal")["value"])
+
+
+def _address(value: _Json) -> str:
+    return _expect(value, "Reference")["value"]
+
+
+def _internal_address(value: _Json) -> str:
+    return _expect(value, "Own")["value"]
+
+
+def _local_id(value: _Json) -> str:
+    return _expect(value, "NonFungibleLocalId")["value"]
+
+
+def _bytes(value: _Json) -> bytes:
+    return bytes.fromhex(_expect(value, "Bytes")["hex"])
+
+
+def _instant(value: _Json) -> datetime:
+    seconds = int(_expect(value, "I64")["value"])
+    return datetime.fromtimestamp(seconds, tz=timezone.utc)
+
+
+def _value(value: _Json) -> _Json:
+    return value
+
+
+def _array(value: _Json, item: _Parser) -> list[Any]:
+    return [item(element) for element in _expect(value, "Array")["elements"]]
+
+
+def _map(value: _Json, key: _Parser, item: _Parser) -> dict[Any, Any]:
+    entries = _expect(value, "Map")["entries"]
+    return {key(entry["key"]): item(entry["value"]) for entry in entries}
+
+
+def _tuple_fields(fields: list[_Json], items: list[_Parser]) -> tuple[Any, ...]:
+    if len(fields) != len(items):
+        raise ValueError(f"Expected {len(items)} fields, got {len(fields)}")
+    return tuple(item(value) for item, value in zip(items, fields))
+
+
+def _tuple(value: _Json, items: list[_Parser]) -> tuple[Any, ...]:
+    return _tuple_fields(_expect(value, "Tuple")["fields"], items)
+
+
+def _named(fields: list[_Json]) -> dict[str, _Json]:
+    return {value["field_name"]: value for value in fields if "field_name" in value}
+
+
+def _struct(value: _Json) -> dict[str, _Json]:
+    return _named(_expect(value, "Tuple")["fields"])
+
+
+def _field(fields: dict[str, _Json], name: str, item: _Parser) -> Any:
+    if name not in fields:
+        raise ValueError(f"Missing field {name}")
+    return item(fields[name])
+
+
+def _optional_field(fields: dict[str, _Json], name: str, item: _Parser) -> Any:
+    return item(fields[name]) if name in fields else None
+
+
+def _variant(value: _Json) -> tuple[int, list[_Json]]:
+    value = _expect(value, "Enum")
+    return int(value["variant_id"]), value["fields"]
+
+
+def _unknown_variant(value: _Json, type_name: str) -> ValueError:
+    variant = value.get("variant_name") or value["variant_id"]
+    return ValueError(f"Unknown variant {variant} of {type_name}")
+
+
+def _unknown(value: _Json) -> UnknownVariant:
+    return UnknownVariant(str(value.get("variant_name") or value["variant_id"]), value)
+
+
+def _option(value: _Json, some: _Parser) -> Any:
+    variant_id, fields = _variant(value)
+    if variant_id == 0:
+        return None
+    if variant_id == 1:
+        return some(fields[0])
+    raise _unknown_variant(value, "Option")
+
+
+def _result(value: _Json, ok: _Parser, err: _Parser) -> Union[Ok[Any], Err[Any]]:
+    variant_id, fields = _variant(value)
+    if variant_id == 0:
+        return Ok(ok(fields[0]))
+    if variant_id == 1:
+        return Err(err(fields[0]))
+    raise _unknown_variant(value, "Result")
+
+
+def _utc_date_time(value: _Json) -> datetime:
+    year, month, day, hour, minute, second = _tuple(value, [_int] * 6)
+    return datetime(year, month, day, hour, minute, second, tzinfo=timezone.utc)
+
+
+def _non_fungible_global_id(value: _Json) -> tuple[str, str]:
+    return _tuple(value, [_address, _local_id])
+
+
+PriceUpdate = tuple[str, int]
+
+
+def parse_PriceUpdate(value: _Json) -> PriceUpdate:
+    return _tuple(value, [_string, _int])
+
+
+@dataclass(frozen=True)
+class PairKey:
+    base: str
+    quote: str
+
+
+def parse_PairKey(value: _Json) -> PairKey:
+    fields = _struct(value)
+    return PairKey(
+        base=_field(fields, "base", _address),
+        quote=_field(fields, "quote", _address),
+    )
+
+
+@dataclass(frozen=True)
+class FeeTier_Low:
+    variant: Literal["Low"] = field(default="Low", init=False)
+
+
+@dataclass(frozen=True)
+class FeeTier_Custom:
+    value: Decimal
+    variant: Literal["Custom"] = field(default="Custom", init=False)
+
+
+@dataclass(frozen=True)
+class FeeTier_Stepped:
+    steps: list[Decimal]
+    variant: Literal["Stepped"] = field(default="Stepped", init=False)
+
+
+FeeTier = Union[FeeTier_Low, FeeTier_Custom, FeeTier_Stepped]
+
+
+def parse_FeeTier(value: _Json) -> FeeTier:
+    variant_id, fields = _variant(value)
+    if variant_id == 0:
+        return FeeTier_Low()
+    if variant_id == 1:
+        return FeeTier_Custom(*_tuple_fields(fields, [_decimal]))
+    if variant_id == 3:
+        named = _named(fields)
+        return FeeTier_Stepped(
+            steps=_field(named, "steps", lambda v: _array(v, _decimal)),
+        )
+    raise _unknown_variant(value, "FeeTier")
+
+
+@dataclass(frozen=True)
+class PoolState:
+    reserves: dict[str, Decimal]
+    fee_tier: FeeTier
+    admin_badge: tuple[str, str]
+    last_swap: Optional[datetime]
+    swap_count: int
+    prices: dict[PairKey, Decimal]
+
+
+def parse_PoolState(value: _Json) -> PoolState:
+    fields = _struct(value)
+    return PoolState(
+        reserves=_field(fields, "reserves", lambda v: _map(v, _address, _decimal)),
+        fee_tier=_field(fields, "fee_tier", parse_FeeTier),
+        admin_badge=_field(fields, "admin_badge", _non_fungible_global_id),
+        last_swap=_field(fields, "last_swap", lambda v: _option(v, _instant)),
+        swap_count=_field(fields, "swap_count", _int),
+        prices=_field(fields, "prices", lambda v: _map(v, parse_PairKey, _decimal)),
+    )
+
+
+@dataclass(frozen=True)
+class SwapEvent:
+    input_amount: Decimal
+    input_resource: str
+    output_amount: Decimal
+    type: str
+
+
+def parse_SwapEvent(value: _Json) -> SwapEvent:
+    fields = _struct(value)
+    return SwapEvent(
+        input_amount=_field(fields, "input_amount", _decimal),
+        input_resource=_field(fields, "input_resource", _address),
+        output_amount=_field(fields, "output_amount", _decimal),
+        type=_field(fields, "type", _string),
+    )
+
+
+STATE_PARSERS: dict[str, _Parser] = {
+    "Pool": parse_PoolState,
+}
+
+EVENT_PARSERS: dict[tuple[str, str], _Parser] = {
+    ("Pool", "SwapEvent"): parse_SwapEvent,
+    ("Pool", "PriceUpdate"): parse_PriceUpdate,
+}
+
+
+def parse_event(
+    emitter_blueprint: str, event_name: str, programmatic_json: _Json
+) -> Any:
+    """Parses the payload of an event, based on the blueprint that emitted it
+    and the name of the event. Returns None for events of other packages."""
+    parser = EVENT_PARSERS.get((emitter_blueprint, event_name))
+    return None if parser is None else parser(programmatic_json)
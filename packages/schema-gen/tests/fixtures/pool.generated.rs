@@ -0,0 +1,40 @@
+// Generated Rust types for Scrypto SBOR types of package address: package_pool
+
+use scrypto::prelude::*;
+
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct PriceUpdate(pub String, pub u64);
+
+#[derive(ScryptoSbor, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PairKey {
+    pub base: ResourceAddress,
+    pub quote: ResourceAddress,
+}
+
+#[derive(ScryptoSbor, Debug, Clone)]
+pub enum FeeTier {
+    #[sbor(discriminator(0))]
+    Low,
+    #[sbor(discriminator(1))]
+    Custom(Decimal),
+    #[sbor(discriminator(3))]
+    Stepped { steps: Vec<Decimal> },
+}
+
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct PoolState {
+    pub reserves: IndexMap<ResourceAddress, Decimal>,
+    pub fee_tier: FeeTier,
+    pub admin_badge: NonFungibleGlobalId,
+    pub last_swap: Option<Instant>,
+    pub swap_count: u64,
+    pub prices: IndexMap<PairKey, Decimal>,
+}
+
+#[derive(ScryptoSbor, Debug, Clone)]
+pub struct SwapEvent {
+    pub input_amount: Decimal,
+    pub input_resource: ResourceAddress,
+    pub output_amount: Decimal,
+    pub r#type: String,
+}
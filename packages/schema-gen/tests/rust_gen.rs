@@ -0,0 +1,71 @@
+//! Compiles the Rust types generated for the pool fixture, and checks that
+//! they decode values encoded by Scrypto. The fixture is regenerated by the
+//! `test_pool_renders_compiling_rust` unit test.
+
+use scrypto::prelude::*;
+
+#[allow(dead_code)]
+#[path = "fixtures/pool.generated.rs"]
+mod pool;
+
+/// The event as the blueprint declares it.
+#[derive(ScryptoSbor)]
+struct SwapEvent {
+    input_amount: Decimal,
+    input_resource: ResourceAddress,
+    output_amount: Decimal,
+    r#type: String,
+}
+
+#[test]
+fn test_events_decode_into_generated_types() {
+    let payload = scrypto_encode(&SwapEvent {
+        input_amount: Decimal::from(10),
+        input_resource: XRD,
+        output_amount: Decimal::from(25),
+        r#type: "exact_input".to_string(),
+    })
+    .unwrap();
+
+    let event: pool::SwapEvent = scrypto_decode(&payload).unwrap();
+    assert_eq!(event.input_amount, Decimal::from(10));
+    assert_eq!(event.input_resource, XRD);
+    assert_eq!(event.output_amount, Decimal::from(25));
+    assert_eq!(event.r#type, "exact_input");
+}
+
+#[test]
+fn test_variants_keep_their_discriminators() {
+    let fee_tier = pool::FeeTier::Stepped {
+        steps: vec![Decimal::ONE],
+    };
+    let value: ScryptoValue =
+        scrypto_decode(&scrypto_encode(&fee_tier).unwrap()).unwrap();
+    let ScryptoValue::Enum { discriminator, .. } = value else {
+        panic!("expected an enum, got {:?}", value);
+    };
+    assert_eq!(discriminator, 3);
+}
+
+#[test]
+fn test_state_round_trips() {
+    let pair = pool::PairKey {
+        base: XRD,
+        quote: XRD,
+    };
+    let state = pool::PoolState {
+        reserves: IndexMap::from_iter([(XRD, Decimal::from(100))]),
+        fee_tier: pool::FeeTier::Custom(Decimal::from(3)),
+        admin_badge: NonFungibleGlobalId::new(
+            XRD,
+            NonFungibleLocalId::integer(1),
+        ),
+        last_swap: Some(Instant::new(1_700_000_000)),
+        swap_count: 42,
+        prices: IndexMap::from_iter([(pair, Decimal::ONE)]),
+    };
+    let payload = scrypto_encode(&state).unwrap();
+
+    let decoded: pool::PoolState = scrypto_decode(&payload).unwrap();
+    assert_eq!(scrypto_encode(&decoded).unwrap(), payload);
+}